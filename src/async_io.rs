@@ -0,0 +1,215 @@
+//! Tokio counterparts of the `std::io` stripping adapters.
+
+use crate::io::{Stripper, READ_CHUNK};
+use crate::DEFAULT_MAX_PENDING;
+use pin_project_lite::pin_project;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, ReadBuf};
+
+pin_project! {
+    /// An [`AsyncRead`] / [`AsyncBufRead`] adapter yielding the wrapped
+    /// reader's bytes with escape sequences removed.
+    ///
+    /// Behaves like [`StripReader`](crate::StripReader): stripped bytes are
+    /// available as soon as each chunk is read, and only an unfinished trailing
+    /// sequence is held back.
+    #[derive(Debug)]
+    pub struct AsyncStripReader<R> {
+        #[pin]
+        inner: R,
+        stripper: Stripper,
+        chunk: Vec<u8>,
+        out: Vec<u8>,
+        pos: usize,
+        eof: bool,
+    }
+}
+
+impl<R: AsyncRead> AsyncStripReader<R> {
+    /// Wrap `inner`, holding back at most [`DEFAULT_MAX_PENDING`] bytes.
+    pub fn new(inner: R) -> Self {
+        AsyncStripReader::with_max_pending(inner, DEFAULT_MAX_PENDING)
+    }
+
+    /// Wrap `inner`, giving up on an unfinished sequence once it is longer
+    /// than `max_pending` bytes and yielding it as text.
+    pub fn with_max_pending(inner: R, max_pending: usize) -> Self {
+        AsyncStripReader {
+            inner,
+            stripper: Stripper::new(max_pending),
+            chunk: vec![0; READ_CHUNK],
+            out: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Mutable reference to the wrapped reader. Reading from it directly
+    /// skips the stripper and desynchronises any held-back bytes.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Pinned mutable reference to the wrapped reader.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner
+    }
+
+    /// Return the wrapped reader, discarding buffered and held-back bytes.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for AsyncStripReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = available.len().min(buf.remaining());
+        buf.put_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead> AsyncBufRead for AsyncStripReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let mut this = self.project();
+        // A chunk made entirely of escapes yields nothing, so keep reading.
+        while *this.pos >= this.out.len() && !*this.eof {
+            let mut read = ReadBuf::new(this.chunk);
+            ready!(this.inner.as_mut().poll_read(cx, &mut read))?;
+            let n = read.filled().len();
+            this.out.clear();
+            *this.pos = 0;
+            if n == 0 {
+                *this.eof = true;
+                this.stripper.finish(this.out);
+            } else {
+                this.stripper.push(&this.chunk[..n], this.out);
+            }
+        }
+        Poll::Ready(Ok(&this.out[*this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        *this.pos = (*this.pos + amt).min(this.out.len());
+    }
+}
+
+pin_project! {
+    /// An [`AsyncWrite`] adapter that removes escape sequences before
+    /// forwarding bytes.
+    ///
+    /// Behaves like [`StripWriter`](crate::StripWriter). To keep backpressure,
+    /// a write is only accepted once the output of the previous one has been
+    /// forwarded. Shut the writer down (e.g. `AsyncWriteExt::shutdown`) to
+    /// forward a held-back tail; dropping it discards the tail.
+    #[derive(Debug)]
+    pub struct AsyncStripWriter<W> {
+        #[pin]
+        inner: W,
+        stripper: Stripper,
+        out: Vec<u8>,
+        pos: usize,
+    }
+}
+
+impl<W: AsyncWrite> AsyncStripWriter<W> {
+    /// Wrap `inner`, holding back at most [`DEFAULT_MAX_PENDING`] bytes.
+    pub fn new(inner: W) -> Self {
+        AsyncStripWriter::with_max_pending(inner, DEFAULT_MAX_PENDING)
+    }
+
+    /// Wrap `inner`, giving up on an unfinished sequence once it is longer
+    /// than `max_pending` bytes and forwarding it as text.
+    pub fn with_max_pending(inner: W, max_pending: usize) -> Self {
+        AsyncStripWriter {
+            inner,
+            stripper: Stripper::new(max_pending),
+            out: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Mutable reference to the wrapped writer. Writing to it directly
+    /// bypasses any buffered or held-back bytes.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Pinned mutable reference to the wrapped writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().inner
+    }
+
+    /// Return the wrapped writer, discarding buffered and held-back bytes.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    // Forward the output of the last accepted write.
+    fn poll_drain(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        while *this.pos < this.out.len() {
+            let n = ready!(this.inner.as_mut().poll_write(cx, &this.out[*this.pos..]))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            *this.pos += n;
+        }
+        this.out.clear();
+        *this.pos = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for AsyncStripWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        ready!(self.as_mut().poll_drain(cx))?;
+        let this = self.as_mut().project();
+        this.stripper.push(buf, this.out);
+        // Start forwarding now; whatever the inner writer refuses or fails to
+        // take waits for the next call, which reports any error. `buf` is
+        // consumed either way, so it must not be reported as refused.
+        let _ = self.poll_drain(cx);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    /// Forwards buffered output and flushes the wrapped writer. An unfinished
+    /// sequence stays held back because later writes may still complete it.
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_drain(cx))?;
+        self.project().inner.poll_flush(cx)
+    }
+
+    /// Forwards buffered output and any held-back tail, then shuts down the
+    /// wrapped writer.
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_drain(cx))?;
+        let this = self.as_mut().project();
+        this.stripper.finish(this.out);
+        ready!(self.as_mut().poll_drain(cx))?;
+        self.project().inner.poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,311 @@
+//! Configurable ANSI regex with cached compilation.
+
+use crate::{
+    EscapeKind, CHARSET_PATTERN, CONTROL_STRING_PATTERN, CSI_7BIT_PATTERN, CSI_8BIT_PATTERN,
+    CSI_STRICT_PATTERN, DEC_HASH_PATTERN, DSR_PATTERN, OSC_PATTERN, VT52_PATTERN,
+};
+use regex::{Matches, Regex};
+use std::borrow::Cow;
+use std::iter::Take;
+use std::sync::{Arc, Mutex};
+
+// The most recently built regexes, least recently used first. Bounded so
+// that building many distinct configurations (say, one `max_osc_len` per
+// request) cannot grow memory without limit.
+static CACHE: Mutex<Vec<(String, Arc<Regex>)>> = Mutex::new(Vec::new());
+const CACHE_CAPACITY: usize = 16;
+
+// Remove the 8-bit C1 alternatives (`0x9B`, `0x9C` and the control string
+// introducers) from a published branch.
+fn without_c1(branch: &str) -> String {
+    let pattern = ["|[\\x90\\x98\\x9E\\x9F]", "|\\x9B", "|\\x9C", "\\x9C"]
+        .iter()
+        .fold(branch.to_owned(), |p, c1| p.replace(c1, ""));
+    debug_assert!(!pattern.contains("\\x9"), "C1 left in {pattern}");
+    pattern
+}
+
+// Replace the single occurrence of `from` in a published branch.
+fn substitute(branch: &str, from: &str, to: &str) -> String {
+    debug_assert!(branch.contains(from), "{from} not in {branch}");
+    branch.replacen(from, to, 1)
+}
+
+/// Builder for an ANSI regex with a chosen set of sequence families.
+///
+/// The defaults reproduce [`ANSI_REGEX_PATTERN`](crate::ANSI_REGEX_PATTERN)
+/// exactly; enabling the DCS / SOS / PM / APC families gives
+/// [`ANSI_REGEX_EXTENDED_PATTERN`](crate::ANSI_REGEX_EXTENDED_PATTERN) and
+/// [`strict_csi`](AnsiRegexBuilder::strict_csi) gives
+/// [`ANSI_REGEX_STRICT_PATTERN`](crate::ANSI_REGEX_STRICT_PATTERN).
+///
+/// ```
+/// use regex_ansi::{AnsiRegexBuilder, EscapeKind};
+///
+/// let re = AnsiRegexBuilder::new()
+///     .families([EscapeKind::Csi])
+///     .only_first(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(re.strip("\x1b[1ma\x1b[0m\x1b]0;t\x07"), "a\x1b[0m\x1b]0;t\x07");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnsiRegexBuilder {
+    only_first: bool,
+    osc: bool,
+    csi: bool,
+    short: bool,
+    charset: bool,
+    dec_hash: bool,
+    device_status: bool,
+    dcs: bool,
+    sos: bool,
+    pm: bool,
+    apc: bool,
+    c1: bool,
+    strict_csi: bool,
+    max_osc_len: Option<usize>,
+}
+
+impl Default for AnsiRegexBuilder {
+    fn default() -> Self {
+        AnsiRegexBuilder {
+            only_first: false,
+            osc: true,
+            csi: true,
+            short: true,
+            charset: true,
+            dec_hash: true,
+            device_status: true,
+            dcs: false,
+            sos: false,
+            pm: false,
+            apc: false,
+            c1: true,
+            strict_csi: false,
+            max_osc_len: None,
+        }
+    }
+}
+
+impl AnsiRegexBuilder {
+    /// The upstream-compatible configuration.
+    pub fn new() -> Self {
+        AnsiRegexBuilder::default()
+    }
+
+    /// Act on the first match only, like ansi-regex's `{onlyFirst: true}`.
+    /// Default `false`.
+    pub fn only_first(mut self, only_first: bool) -> Self {
+        self.only_first = only_first;
+        self
+    }
+
+    /// Enable or disable one family. All families except DCS, SOS, PM and
+    /// APC are enabled by default.
+    pub fn family(mut self, kind: EscapeKind, enabled: bool) -> Self {
+        *self.flag(kind) = enabled;
+        self
+    }
+
+    /// Match exactly the given families.
+    pub fn families(mut self, kinds: impl IntoIterator<Item = EscapeKind>) -> Self {
+        for kind in ALL_KINDS {
+            *self.flag(kind) = false;
+        }
+        for kind in kinds {
+            *self.flag(kind) = true;
+        }
+        self
+    }
+
+    /// Accept the 8-bit C1 forms (`U+009B` CSI, `U+009C` ST and the 8-bit
+    /// string introducers). Default `true`.
+    pub fn c1(mut self, c1: bool) -> Self {
+        self.c1 = c1;
+        self
+    }
+
+    /// Match CSI sequences per ECMA-48 instead of the upstream-compatible
+    /// branch; see [`ansi_regex_strict`](crate::ansi_regex_strict). Default
+    /// `false`.
+    pub fn strict_csi(mut self, strict: bool) -> Self {
+        self.strict_csi = strict;
+        self
+    }
+
+    /// Only match OSC sequences with at most `len` characters between the
+    /// introducer and the terminator. Unbounded by default.
+    pub fn max_osc_len(mut self, len: usize) -> Self {
+        self.max_osc_len = Some(len);
+        self
+    }
+
+    fn flag(&mut self, kind: EscapeKind) -> &mut bool {
+        match kind {
+            EscapeKind::Osc => &mut self.osc,
+            EscapeKind::Csi => &mut self.csi,
+            EscapeKind::Charset => &mut self.charset,
+            EscapeKind::DecHash => &mut self.dec_hash,
+            EscapeKind::Short => &mut self.short,
+            EscapeKind::DeviceStatus => &mut self.device_status,
+            EscapeKind::Dcs => &mut self.dcs,
+            EscapeKind::Sos => &mut self.sos,
+            EscapeKind::Pm => &mut self.pm,
+            EscapeKind::Apc => &mut self.apc,
+        }
+    }
+
+    /// The pattern this configuration compiles to. If no family is enabled it
+    /// never matches.
+    pub fn pattern(&self) -> String {
+        let mut branches = Vec::new();
+        let mut intro: String = [
+            (self.dcs, 'P'),
+            (self.sos, 'X'),
+            (self.pm, '^'),
+            (self.apc, '_'),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|&(_, c)| c)
+        .collect();
+        if !intro.is_empty() {
+            let c1_intro: String = [
+                (self.dcs, "\\x90"),
+                (self.sos, "\\x98"),
+                (self.pm, "\\x9E"),
+                (self.apc, "\\x9F"),
+            ]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|&(_, c)| c)
+            .collect();
+            if intro.starts_with('^') {
+                intro.insert(0, '\\');
+            }
+            let branch = substitute(CONTROL_STRING_PATTERN, "[PX^_]", &format!("[{intro}]"));
+            branches.push(if self.c1 {
+                substitute(&branch, "[\\x90\\x98\\x9E\\x9F]", &format!("[{c1_intro}]"))
+            } else {
+                without_c1(&branch)
+            });
+        }
+        if self.osc {
+            let branch = match self.max_osc_len {
+                None => OSC_PATTERN.to_owned(),
+                Some(n) => substitute(OSC_PATTERN, "*?", &format!("{{0,{n}}}?")),
+            };
+            branches.push(if self.c1 { branch } else { without_c1(&branch) });
+        }
+        if self.csi {
+            if self.strict_csi {
+                branches.push(if self.c1 {
+                    CSI_STRICT_PATTERN.to_owned()
+                } else {
+                    without_c1(CSI_STRICT_PATTERN)
+                });
+            } else {
+                branches.push(CSI_7BIT_PATTERN.to_owned());
+                if self.c1 {
+                    branches.push(CSI_8BIT_PATTERN.to_owned());
+                }
+            }
+        }
+        for (on, branch) in [
+            (self.short, VT52_PATTERN),
+            (self.charset, CHARSET_PATTERN),
+            (self.dec_hash, DEC_HASH_PATTERN),
+            (self.device_status, DSR_PATTERN),
+        ] {
+            if on {
+                branches.push(branch.to_owned());
+            }
+        }
+        if branches.is_empty() {
+            return "[^\\s\\S]".to_owned();
+        }
+        branches.join("|")
+    }
+
+    /// Compile the pattern, or reuse the regex compiled earlier for an equal
+    /// pattern while it is among the last few built. Fails only if the
+    /// pattern exceeds the regex size limit, which a very large
+    /// [`max_osc_len`](AnsiRegexBuilder::max_osc_len) can cause.
+    pub fn build(&self) -> Result<AnsiRegex, regex::Error> {
+        let pattern = self.pattern();
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = match cache.iter().position(|(p, _)| *p == pattern) {
+            Some(i) => cache.remove(i),
+            None => {
+                let regex = Arc::new(Regex::new(&pattern)?);
+                if cache.len() == CACHE_CAPACITY {
+                    cache.remove(0);
+                }
+                (pattern, regex)
+            }
+        };
+        let regex = Arc::clone(&entry.1);
+        cache.push(entry);
+        Ok(AnsiRegex {
+            regex,
+            only_first: self.only_first,
+        })
+    }
+}
+
+const ALL_KINDS: [EscapeKind; 10] = [
+    EscapeKind::Osc,
+    EscapeKind::Csi,
+    EscapeKind::Charset,
+    EscapeKind::DecHash,
+    EscapeKind::Short,
+    EscapeKind::DeviceStatus,
+    EscapeKind::Dcs,
+    EscapeKind::Sos,
+    EscapeKind::Pm,
+    EscapeKind::Apc,
+];
+
+/// A compiled regex from [`AnsiRegexBuilder`] together with its
+/// `only_first` setting. Clones share the compiled regex.
+#[derive(Debug, Clone)]
+pub struct AnsiRegex {
+    regex: Arc<Regex>,
+    only_first: bool,
+}
+
+impl AnsiRegex {
+    /// The underlying compiled regex.
+    pub fn as_regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    /// Whether only the first match is acted on.
+    pub fn is_only_first(&self) -> bool {
+        self.only_first
+    }
+
+    /// Whether `haystack` contains a sequence.
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.regex.is_match(haystack)
+    }
+
+    /// Sequences in `haystack`; at most one with `only_first`.
+    pub fn find_iter<'r, 'h>(&'r self, haystack: &'h str) -> Take<Matches<'r, 'h>> {
+        let limit = if self.only_first { 1 } else { usize::MAX };
+        self.regex.find_iter(haystack).take(limit)
+    }
+
+    /// Replace matched sequences with `rep`; only the first with
+    /// `only_first`.
+    pub fn replace<'h>(&self, haystack: &'h str, rep: &str) -> Cow<'h, str> {
+        let limit = if self.only_first { 1 } else { 0 };
+        self.regex.replacen(haystack, limit, regex::NoExpand(rep))
+    }
+
+    /// Remove matched sequences; only the first with `only_first`.
+    pub fn strip<'h>(&self, haystack: &'h str) -> Cow<'h, str> {
+        self.replace(haystack, "")
+    }
+}
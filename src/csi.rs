@@ -0,0 +1,157 @@
+//! Structured view of Control Sequence Introducer sequences.
+
+/// One `;`-separated CSI parameter together with its `:`-separated
+/// sub-parameters.
+///
+/// Empty fields are kept as `None`, so `38:2::1:2:3` and `38:2:0:1:2:3` stay
+/// distinguishable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsiParam {
+    // Never empty: the main value followed by any sub-parameters.
+    values: Vec<Option<u32>>,
+}
+
+impl CsiParam {
+    /// Main value, or `None` when the field was empty.
+    pub fn value(&self) -> Option<u32> {
+        self.values[0]
+    }
+
+    /// Main value with an empty field read as `default`.
+    pub fn value_or(&self, default: u32) -> u32 {
+        self.values[0].unwrap_or(default)
+    }
+
+    /// Colon-separated sub-parameters following the main value.
+    pub fn subparams(&self) -> &[Option<u32>] {
+        &self.values[1..]
+    }
+
+    /// Whether the parameter carries colon sub-parameters.
+    pub fn has_subparams(&self) -> bool {
+        self.values.len() > 1
+    }
+
+    fn parse(field: &str) -> Option<CsiParam> {
+        let values = field
+            .split(':')
+            .map(|v| {
+                if v.is_empty() {
+                    Some(None)
+                } else if v.bytes().all(|b| b.is_ascii_digit()) {
+                    // Oversized values saturate rather than wrap.
+                    Some(Some(v.parse().unwrap_or(u32::MAX)))
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(CsiParam { values })
+    }
+}
+
+/// A parsed CSI sequence: `ESC [` (or `0x9B`), an optional private marker,
+/// parameters, intermediate bytes and a final byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Csi<'a> {
+    raw: &'a str,
+    private_marker: Option<char>,
+    params: Vec<CsiParam>,
+    intermediates: &'a str,
+    final_byte: char,
+}
+
+impl<'a> Csi<'a> {
+    /// Parse a complete CSI sequence such as `"\x1b[38:2::12:34:56m"`.
+    ///
+    /// Returns `None` when `seq` is not a CSI sequence or its body does not
+    /// follow the ECMA-48 layout (private marker, parameter bytes, intermediate
+    /// bytes). The final byte is taken as matched, so the looser finals accepted
+    /// by the upstream pattern (e.g. `ESC [ 5`) still parse.
+    pub fn parse(seq: &'a str) -> Option<Csi<'a>> {
+        let body = seq
+            .strip_prefix("\x1b[")
+            .or_else(|| seq.strip_prefix('\u{9B}'))?;
+        let final_byte = body.chars().next_back().filter(char::is_ascii)?;
+        let body = &body[..body.len() - 1];
+
+        let (private_marker, body) = match body.chars().next() {
+            Some(c @ ('<' | '=' | '>' | '?')) => (Some(c), &body[1..]),
+            _ => (None, body),
+        };
+        let params_end = body
+            .find(|c: char| !matches!(c, '0'..='9' | ':' | ';'))
+            .unwrap_or(body.len());
+        let (params, rest) = body.split_at(params_end);
+        let inter_end = rest
+            .find(|c: char| !matches!(c, ' '..='/'))
+            .unwrap_or(rest.len());
+        if inter_end != rest.len() {
+            return None;
+        }
+        let params = if params.is_empty() {
+            Vec::new()
+        } else {
+            params
+                .split(';')
+                .map(CsiParam::parse)
+                .collect::<Option<Vec<_>>>()?
+        };
+        Some(Csi {
+            raw: seq,
+            private_marker,
+            params,
+            intermediates: rest,
+            final_byte,
+        })
+    }
+
+    /// Raw text of the sequence.
+    pub fn as_str(&self) -> &'a str {
+        self.raw
+    }
+
+    /// Private parameter marker (`?`, `>`, `=` or `<`), if present.
+    pub fn private_marker(&self) -> Option<char> {
+        self.private_marker
+    }
+
+    /// Parameters in order; empty for sequences like `ESC [ m`.
+    pub fn params(&self) -> &[CsiParam] {
+        &self.params
+    }
+
+    /// Main value of parameter `index`, `None` if missing or empty.
+    pub fn param(&self, index: usize) -> Option<u32> {
+        self.params.get(index).and_then(CsiParam::value)
+    }
+
+    /// Intermediate bytes (0x20–0x2F) preceding the final byte.
+    pub fn intermediates(&self) -> &'a str {
+        self.intermediates
+    }
+
+    /// Final byte selecting the control function (`m`, `H`, `J`, ...).
+    pub fn final_byte(&self) -> char {
+        self.final_byte
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_non_csi() {
+        assert!(Csi::parse("\x1b]0;t\x07").is_none());
+        assert!(Csi::parse("\x1b[").is_none());
+        // Upstream prefix bytes outside the ECMA-48 layout.
+        assert!(Csi::parse("\x1b[[A").is_none());
+    }
+
+    #[test]
+    fn oversized_value_saturates() {
+        let csi = Csi::parse("\x1b[99999999999H").unwrap();
+        assert_eq!(csi.param(0), Some(u32::MAX));
+    }
+}
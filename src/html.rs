@@ -0,0 +1,429 @@
+//! Conversion of styled terminal output to HTML.
+
+use crate::style::osc8_parts;
+use crate::{AnsiTokenizer, Color, Style, StyleState, Token, UnderlineStyle};
+use std::fmt::Write as _;
+
+/// An RGB color used by the HTML output.
+pub type Rgb = (u8, u8, u8);
+
+/// The xterm default palette for the 16 basic colors.
+pub const XTERM_PALETTE: [Rgb; 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Options for [`HtmlOptions::to_html`].
+///
+/// Text runs become `<span>`s carrying their style, either as inline `style`
+/// attributes (the default) or as CSS classes, and OSC 8 hyperlinks become
+/// `<a href>` elements. The output is a fragment meant to be placed inside a
+/// `<pre>`; text is HTML-escaped and control characters other than tab, line
+/// feed and carriage return are dropped. Blink is rendered only with
+/// [`css_classes`](HtmlOptions::css_classes), since an inline `style` cannot
+/// declare the animation it needs.
+///
+/// ```
+/// use regex_ansi::HtmlOptions;
+///
+/// let html = HtmlOptions::new().to_html("\x1b[1;31mfail\x1b[0m <ok>");
+/// assert_eq!(html, "<span style=\"font-weight:bold;color:#cd0000\">fail</span> &lt;ok&gt;");
+///
+/// let html = HtmlOptions::new().css_classes(true).to_html("\x1b[1;31mfail");
+/// assert_eq!(html, "<span class=\"ansi-bold ansi-fg-1\">fail</span>");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlOptions {
+    classes: bool,
+    class_prefix: String,
+    palette: [Rgb; 16],
+    foreground: Rgb,
+    background: Rgb,
+    hyperlinks: bool,
+    schemes: Vec<String>,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        HtmlOptions {
+            classes: false,
+            class_prefix: "ansi-".to_owned(),
+            palette: XTERM_PALETTE,
+            foreground: XTERM_PALETTE[7],
+            background: XTERM_PALETTE[0],
+            hyperlinks: true,
+            schemes: vec!["http".to_owned(), "https".to_owned()],
+        }
+    }
+}
+
+impl HtmlOptions {
+    /// Inline styles, the xterm palette and `http` / `https` links.
+    pub fn new() -> Self {
+        HtmlOptions::default()
+    }
+
+    /// Emit CSS classes instead of inline styles. Default `false`.
+    ///
+    /// Attributes become `{prefix}bold`, `dim`, `italic`, `underline` (plus
+    /// `underline-double`, `-curly`, `-dotted` or `-dashed`), `blink`,
+    /// `hidden`, `strike` and `overline`; the 16 basic colors become
+    /// `{prefix}fg-N` / `{prefix}bg-N`. 256-palette and truecolor values stay
+    /// inline. [`stylesheet`](HtmlOptions::stylesheet) provides matching rules.
+    /// Inline styles have no equivalent of `blink`, so blinking text gets no
+    /// span unless another attribute is set.
+    pub fn css_classes(mut self, classes: bool) -> Self {
+        self.classes = classes;
+        self
+    }
+
+    /// Prefix of every class name. Default `ansi-`.
+    pub fn class_prefix(mut self, prefix: &str) -> Self {
+        self.class_prefix = prefix.to_owned();
+        self
+    }
+
+    /// Colors of the 16 basic colors, also used for palette indexes 0–15.
+    /// Default [`XTERM_PALETTE`].
+    pub fn palette(mut self, palette: [Rgb; 16]) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Default foreground and background, used only to render inverse video
+    /// when a color is not set. Default palette colors 7 and 0.
+    pub fn default_colors(mut self, foreground: Rgb, background: Rgb) -> Self {
+        self.foreground = foreground;
+        self.background = background;
+        self
+    }
+
+    /// Turn OSC 8 hyperlinks into `<a href>`. Default `true`.
+    pub fn hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    /// URL schemes turned into links, compared case-insensitively; other
+    /// links keep only their label. Default `http` and `https`.
+    pub fn link_schemes<I, S>(mut self, schemes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.schemes = schemes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Convert `s` to an HTML fragment.
+    pub fn to_html(&self, s: &str) -> String {
+        let mut out = String::with_capacity(s.len() * 2);
+        let mut state = StyleState::new();
+        // Style of the current run and whether a `<span>` was opened for it.
+        let mut span: Option<(Style, bool)> = None;
+        let mut link: Option<String> = None;
+        for token in AnsiTokenizer::new(s) {
+            let text = match token {
+                Token::Escape(seq) => {
+                    state.apply_escape(&seq);
+                    continue;
+                }
+                Token::Text(text) => text,
+            };
+            let href = state
+                .hyperlink()
+                .and_then(osc8_parts)
+                .map(|(_, url)| url)
+                .filter(|url| self.allows_url(url));
+            if href != link.as_deref() {
+                if let Some((_, true)) = span.take() {
+                    out.push_str("</span>");
+                }
+                if link.take().is_some() {
+                    out.push_str("</a>");
+                }
+                if let Some(href) = href {
+                    out.push_str("<a href=\"");
+                    push_escaped(href, &mut out);
+                    out.push_str("\">");
+                    link = Some(href.to_owned());
+                }
+            }
+            if span.as_ref().map(|(style, _)| style) != Some(state.style()) {
+                if let Some((_, true)) = span.take() {
+                    out.push_str("</span>");
+                }
+                if !state.style().is_default() {
+                    let open = self.push_open_span(state.style(), &mut out);
+                    span = Some((state.style().clone(), open));
+                }
+            }
+            push_escaped(text, &mut out);
+        }
+        if let Some((_, true)) = span {
+            out.push_str("</span>");
+        }
+        if link.is_some() {
+            out.push_str("</a>");
+        }
+        out
+    }
+
+    /// CSS rules for the classes emitted with
+    /// [`css_classes`](HtmlOptions::css_classes), using this palette.
+    pub fn stylesheet(&self) -> String {
+        let p = &self.class_prefix;
+        let mut out = String::new();
+        for (n, &rgb) in self.palette.iter().enumerate() {
+            let hex = hex(rgb);
+            writeln!(out, ".{p}fg-{n}{{color:{hex}}}").expect("writing to a String cannot fail");
+            writeln!(out, ".{p}bg-{n}{{background-color:{hex}}}")
+                .expect("writing to a String cannot fail");
+        }
+        for (class, rule) in [
+            ("bold", "font-weight:bold"),
+            ("dim", "opacity:0.5"),
+            ("italic", "font-style:italic"),
+            ("hidden", "visibility:hidden"),
+            ("underline-double", "text-decoration-style:double"),
+            ("underline-curly", "text-decoration-style:wavy"),
+            ("underline-dotted", "text-decoration-style:dotted"),
+            ("underline-dashed", "text-decoration-style:dashed"),
+        ] {
+            writeln!(out, ".{p}{class}{{{rule}}}").expect("writing to a String cannot fail");
+        }
+        writeln!(
+            out,
+            ".{p}blink{{animation:{p}blink 1s steps(2,start) infinite}}\n\
+             @keyframes {p}blink{{to{{visibility:hidden}}}}"
+        )
+        .expect("writing to a String cannot fail");
+        // Every combination of the three lines, since each class would
+        // otherwise overwrite the others' `text-decoration-line`.
+        for mask in 1..8u8 {
+            let lines = decoration_lines(mask & 1 != 0, mask & 2 != 0, mask & 4 != 0);
+            let selector: String = [(1, "underline"), (2, "strike"), (4, "overline")]
+                .iter()
+                .filter(|(bit, _)| mask & bit != 0)
+                .map(|(_, class)| format!(".{p}{class}"))
+                .collect();
+            writeln!(out, "{selector}{{text-decoration-line:{lines}}}")
+                .expect("writing to a String cannot fail");
+        }
+        out
+    }
+
+    fn allows_url(&self, url: &str) -> bool {
+        self.hyperlinks
+            && url.split_once(':').is_some_and(|(scheme, _)| {
+                self.schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme))
+            })
+    }
+
+    // Write the opening tag for `style`, unless it has neither classes nor
+    // CSS; returns whether a tag was written.
+    fn push_open_span(&self, style: &Style, out: &mut String) -> bool {
+        let (foreground, background) = if style.inverse {
+            (
+                Some(
+                    style
+                        .background
+                        .map_or(Paint::Rgb(self.background), |c| self.paint(c)),
+                ),
+                Some(
+                    style
+                        .foreground
+                        .map_or(Paint::Rgb(self.foreground), |c| self.paint(c)),
+                ),
+            )
+        } else {
+            (
+                style.foreground.map(|c| self.paint(c)),
+                style.background.map(|c| self.paint(c)),
+            )
+        };
+        let mut classes = Vec::new();
+        let mut css = String::new();
+        let mut rule = |name: &str, value: &str| {
+            if !css.is_empty() {
+                css.push(';');
+            }
+            css.push_str(name);
+            css.push(':');
+            css.push_str(value);
+        };
+        if self.classes {
+            for (on, class) in [
+                (style.bold, "bold"),
+                (style.dim, "dim"),
+                (style.italic, "italic"),
+                (style.underline.is_some(), "underline"),
+                (style.blink, "blink"),
+                (style.hidden, "hidden"),
+                (style.strike, "strike"),
+                (style.overline, "overline"),
+            ] {
+                if on {
+                    classes.push(class.to_owned());
+                }
+            }
+            if let Some(shape) = style.underline.and_then(underline_shape) {
+                classes.push(format!("underline-{}", shape.0));
+            }
+        } else {
+            for (on, name, value) in [
+                (style.bold, "font-weight", "bold"),
+                (style.dim, "opacity", "0.5"),
+                (style.italic, "font-style", "italic"),
+                (style.hidden, "visibility", "hidden"),
+            ] {
+                if on {
+                    rule(name, value);
+                }
+            }
+            if style.underline.is_some() || style.strike || style.overline {
+                let lines =
+                    decoration_lines(style.underline.is_some(), style.strike, style.overline);
+                rule("text-decoration-line", &lines);
+            }
+            if let Some(shape) = style.underline.and_then(underline_shape) {
+                rule("text-decoration-style", shape.1);
+            }
+        }
+        for (paint, name, class) in [
+            (foreground, "color", "fg"),
+            (background, "background-color", "bg"),
+        ] {
+            match paint {
+                Some(Paint::Basic(n)) if self.classes => classes.push(format!("{class}-{n}")),
+                Some(Paint::Basic(n)) => rule(name, &hex(self.palette[n as usize])),
+                Some(Paint::Rgb(rgb)) => rule(name, &hex(rgb)),
+                None => {}
+            }
+        }
+        if let Some(c) = style.underline_color {
+            let rgb = match self.paint(c) {
+                Paint::Basic(n) => self.palette[n as usize],
+                Paint::Rgb(rgb) => rgb,
+            };
+            rule("text-decoration-color", &hex(rgb));
+        }
+        if classes.is_empty() && css.is_empty() {
+            return false;
+        }
+        out.push_str("<span");
+        if !classes.is_empty() {
+            out.push_str(" class=\"");
+            for (i, class) in classes.iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                }
+                out.push_str(&self.class_prefix);
+                out.push_str(class);
+            }
+            out.push('"');
+        }
+        if !css.is_empty() {
+            out.push_str(" style=\"");
+            out.push_str(&css);
+            out.push('"');
+        }
+        out.push('>');
+        true
+    }
+
+    fn paint(&self, color: Color) -> Paint {
+        match color {
+            Color::Basic(n) | Color::Indexed(n) if n < 16 => Paint::Basic(n),
+            Color::Basic(n) => Paint::Basic(n & 15),
+            Color::Indexed(n) => Paint::Rgb(indexed_rgb(n)),
+            Color::Rgb(r, g, b) => Paint::Rgb((r, g, b)),
+        }
+    }
+}
+
+// A color resolved for output: a palette slot (a class in class mode) or a
+// fixed RGB value.
+#[derive(Debug, Clone, Copy)]
+enum Paint {
+    Basic(u8),
+    Rgb(Rgb),
+}
+
+// Class suffix and CSS `text-decoration-style` of a non-single underline.
+fn underline_shape(u: UnderlineStyle) -> Option<(&'static str, &'static str)> {
+    match u {
+        UnderlineStyle::Single => None,
+        UnderlineStyle::Double => Some(("double", "double")),
+        UnderlineStyle::Curly => Some(("curly", "wavy")),
+        UnderlineStyle::Dotted => Some(("dotted", "dotted")),
+        UnderlineStyle::Dashed => Some(("dashed", "dashed")),
+    }
+}
+
+fn decoration_lines(underline: bool, strike: bool, overline: bool) -> String {
+    [
+        (underline, "underline"),
+        (strike, "line-through"),
+        (overline, "overline"),
+    ]
+    .iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, line)| *line)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+// Colors 16–231 of the 256-color palette are a 6×6×6 cube, 232–255 a gray
+// ramp; 0–15 are resolved through the configured palette instead.
+fn indexed_rgb(n: u8) -> Rgb {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    if n >= 232 {
+        let v = 8 + 10 * (n - 232);
+        return (v, v, v);
+    }
+    let i = n.saturating_sub(16);
+    (
+        LEVELS[usize::from(i / 36)],
+        LEVELS[usize::from(i / 6 % 6)],
+        LEVELS[usize::from(i % 6)],
+    )
+}
+
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn push_escaped(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+}
+
+/// Shorthand for [`HtmlOptions::new`]`.to_html(s)`.
+pub fn to_html(s: &str) -> String {
+    HtmlOptions::new().to_html(s)
+}
@@ -0,0 +1,384 @@
+//! OSC 8 hyperlink construction, extraction and rewriting.
+
+use crate::style::{osc8_parts, osc_terminator};
+use crate::{AnsiTokenizer, EscapeKind, Token};
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// String terminator that ends an OSC sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Terminator {
+    /// `BEL` (`0x07`), the xterm convention.
+    Bel,
+    /// `ESC \`, the 7-bit ST.
+    #[default]
+    St,
+    /// `0x9C`, the 8-bit C1 ST.
+    C1St,
+}
+
+impl Terminator {
+    /// The terminator as it appears in the sequence.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Terminator::Bel => "\x07",
+            Terminator::St => "\x1b\\",
+            Terminator::C1St => "\u{9C}",
+        }
+    }
+
+    fn of(seq: &str) -> Terminator {
+        match osc_terminator(seq) {
+            "\x07" => Terminator::Bel,
+            "\u{9C}" => Terminator::C1St,
+            _ => Terminator::St,
+        }
+    }
+}
+
+/// An OSC 8 hyperlink found by [`hyperlinks`]: the opening sequence, the
+/// label and the sequence that closed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink<'a> {
+    url: &'a str,
+    params: &'a str,
+    label: &'a str,
+    label_range: Range<usize>,
+    range: Range<usize>,
+    terminator: Terminator,
+}
+
+impl<'a> Hyperlink<'a> {
+    /// Target URI, exactly as written in the opening sequence.
+    pub fn url(&self) -> &'a str {
+        self.url
+    }
+
+    /// Raw `:`-separated `key=value` parameters, e.g. `id=1`; often empty.
+    pub fn params(&self) -> &'a str {
+        self.params
+    }
+
+    /// Value of the parameter `key`.
+    pub fn param(&self, key: &str) -> Option<&'a str> {
+        self.params
+            .split(':')
+            .filter_map(|p| p.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+
+    /// The `id` parameter that lets terminals join separate runs into one link.
+    pub fn id(&self) -> Option<&'a str> {
+        self.param("id")
+    }
+
+    /// Text between the opening and closing sequences, including any other
+    /// escape sequences it contains.
+    pub fn label(&self) -> &'a str {
+        self.label
+    }
+
+    /// Byte range of [`label`](Hyperlink::label) in the input.
+    pub fn label_range(&self) -> Range<usize> {
+        self.label_range.clone()
+    }
+
+    /// Byte range of the whole link in the input, from the start of the
+    /// opening sequence to the end of the closing one.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Terminator of the opening sequence.
+    pub fn terminator(&self) -> Terminator {
+        self.terminator
+    }
+}
+
+/// Builder for an OSC 8 hyperlink with parameters and a chosen terminator.
+///
+/// The URL and parameter values are percent-encoded where needed so the
+/// sequence cannot end early or be misread: every byte outside printable
+/// ASCII (including BEL, ESC and `0x9C`), spaces, and in parameters also `:`,
+/// `;` and `=`. Existing `%` escapes are left as they are.
+///
+/// ```
+/// use regex_ansi::{HyperlinkBuilder, Terminator};
+///
+/// let link = HyperlinkBuilder::new("https://example.com/a b")
+///     .id("nav")
+///     .terminator(Terminator::Bel)
+///     .build("docs");
+/// assert_eq!(link, "\x1b]8;id=nav;https://example.com/a%20b\x07docs\x1b]8;;\x07");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperlinkBuilder {
+    url: String,
+    params: Vec<(String, String)>,
+    terminator: Terminator,
+}
+
+impl HyperlinkBuilder {
+    /// Link to `url`, without parameters, terminated by ST.
+    pub fn new(url: &str) -> Self {
+        HyperlinkBuilder {
+            url: percent_encode(url, ""),
+            params: Vec::new(),
+            terminator: Terminator::default(),
+        }
+    }
+
+    /// Set the `id` parameter, which terminals use to join separate runs
+    /// (e.g. across wrapped lines) into one link.
+    pub fn id(self, id: &str) -> Self {
+        self.param("id", id)
+    }
+
+    /// Set a `key=value` parameter, replacing an earlier value for `key`.
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        let key = percent_encode(key, PARAM_RESERVED);
+        let value = percent_encode(value, PARAM_RESERVED);
+        match self.params.iter_mut().find(|(k, _)| *k == key) {
+            Some(param) => param.1 = value,
+            None => self.params.push((key, value)),
+        }
+        self
+    }
+
+    /// Terminator of both sequences. Default [`Terminator::St`].
+    pub fn terminator(mut self, terminator: Terminator) -> Self {
+        self.terminator = terminator;
+        self
+    }
+
+    /// The opening sequence alone.
+    pub fn open(&self) -> String {
+        let mut out = String::from("\x1b]8;");
+        for (i, (key, value)) in self.params.iter().enumerate() {
+            if i > 0 {
+                out.push(':');
+            }
+            out.push_str(key);
+            out.push('=');
+            out.push_str(value);
+        }
+        out.push(';');
+        out.push_str(&self.url);
+        out.push_str(self.terminator.as_str());
+        out
+    }
+
+    /// The closing sequence alone.
+    pub fn close(&self) -> String {
+        format!("\x1b]8;;{}", self.terminator.as_str())
+    }
+
+    /// `label` wrapped in the opening and closing sequences.
+    pub fn build(&self, label: &str) -> String {
+        let mut out = self.open();
+        out.push_str(label);
+        out.push_str(&self.close());
+        out
+    }
+}
+
+/// `label` linked to `url` with ST terminators; see [`HyperlinkBuilder`].
+///
+/// ```
+/// use regex_ansi::{hyperlink, hyperlinks};
+///
+/// let s = hyperlink("https://example.com", "docs");
+/// assert_eq!(s, "\x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x1b\\");
+/// assert_eq!(hyperlinks(&s).next().unwrap().label(), "docs");
+/// ```
+pub fn hyperlink(url: &str, label: &str) -> String {
+    HyperlinkBuilder::new(url).build(label)
+}
+
+const PARAM_RESERVED: &str = ":;=";
+
+// Percent-encode the UTF-8 bytes of every character outside `!`..=`~`, and
+// of the characters in `reserved`.
+fn percent_encode(s: &str, reserved: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii_graphic() && !reserved.contains(c) {
+            out.push(c);
+        } else {
+            for b in c.encode_utf8(&mut [0; 4]).bytes() {
+                write!(out, "%{b:02X}").expect("writing to a String cannot fail");
+            }
+        }
+    }
+    out
+}
+
+// Opening sequence of a link that has not been closed yet.
+#[derive(Debug, Clone)]
+struct Open<'a> {
+    url: &'a str,
+    params: &'a str,
+    start: usize,
+    end: usize,
+    terminator: Terminator,
+}
+
+/// Iterator over the OSC 8 hyperlinks of a string; see [`hyperlinks`].
+#[derive(Debug, Clone)]
+pub struct Hyperlinks<'a> {
+    input: &'a str,
+    tokens: AnsiTokenizer<'a>,
+    open: Option<Open<'a>>,
+}
+
+impl<'a> Hyperlinks<'a> {
+    fn link(&self, open: Open<'a>, label_end: usize, end: usize) -> Hyperlink<'a> {
+        Hyperlink {
+            url: open.url,
+            params: open.params,
+            label: &self.input[open.end..label_end],
+            label_range: open.end..label_end,
+            range: open.start..end,
+            terminator: open.terminator,
+        }
+    }
+}
+
+impl<'a> Iterator for Hyperlinks<'a> {
+    type Item = Hyperlink<'a>;
+
+    fn next(&mut self) -> Option<Hyperlink<'a>> {
+        loop {
+            let start = self.tokens.offset();
+            let Some(token) = self.tokens.next() else {
+                // A link left open runs to the end of the input.
+                let end = self.input.len();
+                return self.open.take().map(|open| self.link(open, end, end));
+            };
+            let Token::Escape(seq) = token else {
+                continue;
+            };
+            if seq.kind() != EscapeKind::Osc {
+                continue;
+            }
+            let Some((params, url)) = osc8_parts(seq.as_str()) else {
+                continue;
+            };
+            let end = self.tokens.offset();
+            let next = (!url.is_empty()).then(|| Open {
+                url,
+                params,
+                start,
+                end,
+                terminator: Terminator::of(seq.as_str()),
+            });
+            match self.open.take() {
+                // An explicit close ends the link after the close sequence.
+                Some(open) if next.is_none() => return Some(self.link(open, start, end)),
+                // A new opener implicitly ends the previous link before it.
+                Some(open) => {
+                    self.open = next;
+                    return Some(self.link(open, start, start));
+                }
+                // A close with nothing open is ignored.
+                None => self.open = next,
+            }
+        }
+    }
+}
+
+/// Iterate over the OSC 8 hyperlinks in `s`, pairing each opening sequence
+/// with the close that follows it.
+///
+/// A link opened while another is open ends the previous one, as it does on
+/// a terminal; a link never closed runs to the end of the input.
+///
+/// ```
+/// use regex_ansi::hyperlinks;
+///
+/// let s = "see \x1b]8;id=7;https://example.com\x1b\\docs\x1b]8;;\x1b\\ now";
+/// let link = hyperlinks(s).next().unwrap();
+/// assert_eq!(link.url(), "https://example.com");
+/// assert_eq!(link.id(), Some("7"));
+/// assert_eq!(link.label(), "docs");
+/// assert_eq!(&s[link.range()], "\x1b]8;id=7;https://example.com\x1b\\docs\x1b]8;;\x1b\\");
+/// ```
+pub fn hyperlinks(s: &str) -> Hyperlinks<'_> {
+    Hyperlinks {
+        input: s,
+        tokens: AnsiTokenizer::new(s),
+        open: None,
+    }
+}
+
+/// Rewrite the target of every hyperlink in `s`.
+///
+/// `f` returns the new URL, keeping the link's parameters and terminator, or
+/// `None` to remove the link and keep its label as plain text. A new URL is
+/// percent-encoded like [`HyperlinkBuilder`] does, so it cannot end the
+/// sequence early. Borrows `s` when nothing changed.
+///
+/// ```
+/// use regex_ansi::map_hyperlinks;
+///
+/// let s = "\x1b]8;;http://a.test\x07a\x1b]8;;\x07 \x1b]8;;ftp://b.test\x07b\x1b]8;;\x07";
+/// let out = map_hyperlinks(s, |link| {
+///     let rest = link.url().strip_prefix("http://")?;
+///     Some(format!("https://{rest}"))
+/// });
+/// assert_eq!(out, "\x1b]8;;https://a.test\x07a\x1b]8;;\x07 b");
+/// ```
+pub fn map_hyperlinks<'a, F>(s: &'a str, mut f: F) -> Cow<'a, str>
+where
+    F: FnMut(&Hyperlink<'a>) -> Option<String>,
+{
+    let mut out = String::new();
+    let mut last = 0;
+    for link in hyperlinks(s) {
+        let (label, range) = (link.label_range(), link.range());
+        out.push_str(&s[last..range.start]);
+        let keep = match f(&link) {
+            Some(url) if url == link.url() => {
+                out.push_str(&s[range.start..label.start]);
+                true
+            }
+            Some(url) => {
+                out.push_str("\x1b]8;");
+                out.push_str(link.params());
+                out.push(';');
+                out.push_str(&percent_encode(&url, ""));
+                out.push_str(link.terminator().as_str());
+                true
+            }
+            None => false,
+        };
+        out.push_str(link.label());
+        if keep {
+            out.push_str(&s[label.end..range.end]);
+        }
+        last = range.end;
+    }
+    if last == 0 {
+        return Cow::Borrowed(s);
+    }
+    out.push_str(&s[last..]);
+    if out == s {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(out)
+    }
+}
+
+/// Remove every hyperlink from `s`, keeping the labels.
+///
+/// ```
+/// use regex_ansi::remove_hyperlinks;
+///
+/// let s = "\x1b]8;;https://example.com\x1b\\\x1b[1mdocs\x1b[0m\x1b]8;;\x1b\\";
+/// assert_eq!(remove_hyperlinks(s), "\x1b[1mdocs\x1b[0m");
+/// ```
+pub fn remove_hyperlinks(s: &str) -> Cow<'_, str> {
+    map_hyperlinks(s, |_| None)
+}
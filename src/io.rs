@@ -0,0 +1,338 @@
+//! Streaming adapters that strip escape sequences from `std::io` streams.
+
+use crate::strip::{continues_char, find_c1_at};
+use std::io::{self, BufRead, Read, Write};
+
+/// Default cap on the bytes held back while waiting for a sequence to finish.
+pub const DEFAULT_MAX_PENDING: usize = 64 * 1024;
+
+pub(crate) const READ_CHUNK: usize = 8 * 1024;
+
+const ESC: u8 = 0x1B;
+const C1_CSI: u8 = 0x9B;
+const C1_ST: u8 = 0x9C;
+// Lead byte of the UTF-8 encodings of U+009B / U+009C.
+const C2: u8 = 0xC2;
+
+/// Incremental stripper shared by the stream adapters.
+///
+/// Output is identical to [`strip_ansi_bytes`](crate::strip_ansi_bytes) on
+/// the concatenated input, except when an unfinished sequence grows past
+/// `max_pending` bytes; it is then given up on and passed through as text.
+#[derive(Debug, Clone)]
+pub(crate) struct Stripper {
+    // The last few bytes already emitted, kept to tell whether a 0x9B / 0x9C
+    // continues a UTF-8 character, followed by the undecided input.
+    pending: Vec<u8>,
+    context: usize,
+    max_pending: usize,
+}
+
+impl Stripper {
+    pub(crate) fn new(max_pending: usize) -> Self {
+        Stripper {
+            pending: Vec::new(),
+            context: 0,
+            max_pending,
+        }
+    }
+
+    /// Feed `input`, appending every byte that can already be decided to `out`.
+    pub(crate) fn push(&mut self, input: &[u8], out: &mut Vec<u8>) {
+        self.pending.extend_from_slice(input);
+        let mut hold = hold_point(&self.pending, self.context);
+        if self.pending.len() - hold > self.max_pending {
+            hold = self.pending.len();
+        }
+        self.emit(hold, out);
+    }
+
+    /// Flush everything still held back, as the regex would treat it at end of
+    /// input.
+    pub(crate) fn finish(&mut self, out: &mut Vec<u8>) {
+        self.emit(self.pending.len(), out);
+    }
+
+    fn emit(&mut self, upto: usize, out: &mut Vec<u8>) {
+        if upto == self.context {
+            return;
+        }
+        let buf = &self.pending[..upto];
+        let mut last = self.context;
+        while let Some(m) = find_c1_at(buf, last) {
+            out.extend_from_slice(&buf[last..m.start]);
+            last = m.end;
+        }
+        out.extend_from_slice(&buf[last..]);
+        self.context = upto.min(3);
+        self.pending.drain(..upto - self.context);
+    }
+}
+
+// Start of the earliest trailing sequence that more input could still turn
+// into a different or longer match, or `buf.len()` if everything is decided.
+// Bytes before `from` have already been emitted.
+//
+// Only the last two ESC bytes and the last 0x9B can qualify: an OSC body may
+// not contain ESC except as the first byte of its terminator, and a CSI body
+// may contain neither ESC nor 0x9B. A 0x9B that continues a UTF-8 character
+// is text; one whose character is still incomplete may yet be either. A
+// trailing `C2` may still become U+009B.
+fn hold_point(buf: &[u8], from: usize) -> usize {
+    let mut hold = buf.len();
+    if buf.len() > from && buf.last() == Some(&C2) {
+        hold -= 1;
+    }
+    let last_esc = buf[from..]
+        .iter()
+        .rposition(|&b| b == ESC)
+        .map(|p| p + from);
+    let prev_esc = last_esc.and_then(|l| {
+        buf[from..l]
+            .iter()
+            .rposition(|&b| b == ESC)
+            .map(|p| p + from)
+    });
+    let last_csi = buf[from..]
+        .iter()
+        .rposition(|&b| b == C1_CSI)
+        .map(|p| p + from)
+        .filter(|&p| continues_char(buf, p) != Some(true))
+        .map(|p| {
+            if p > from && buf[p - 1] == C2 {
+                p - 1
+            } else {
+                p
+            }
+        });
+    for p in [prev_esc, last_esc, last_csi].into_iter().flatten() {
+        if is_open(buf, p) {
+            hold = hold.min(p);
+        }
+    }
+    hold
+}
+
+// Whether the sequence starting with an introducer at `start` and running to
+// the end of the buffer is a prefix that further bytes could extend.
+// Over-approximating is safe: it only delays output.
+fn is_open(buf: &[u8], start: usize) -> bool {
+    let seq = &buf[start..];
+    match seq[0] {
+        C2 => return csi_open(&seq[2..]),
+        C1_CSI => return csi_open(&seq[1..]),
+        _ => {}
+    }
+    match seq.get(1) {
+        None => true,
+        Some(b']') => {
+            let body = start + 2;
+            let end = buf[body..].iter().enumerate().find_map(|(i, &b)| match b {
+                0x07 => Some(false),
+                ESC => Some(body + i == buf.len() - 1),
+                C1_ST => match continues_char(buf, body + i) {
+                    Some(true) => None,
+                    Some(false) => Some(false),
+                    None => Some(true),
+                },
+                _ => None,
+            });
+            end.unwrap_or(true)
+        }
+        Some(b'[') => csi_open(&seq[2..]),
+        Some(b'(' | b')' | b'#') => seq.len() == 2,
+        // ESC 7 / ESC 8 are complete short escapes; other digits may become `ESC <n> n`.
+        Some(b'7' | b'8') => false,
+        Some(b'0'..=b'9') => seq[1..].iter().all(u8::is_ascii_digit),
+        Some(_) => false,
+    }
+}
+
+fn csi_open(body: &[u8]) -> bool {
+    body.iter().all(|&b| {
+        matches!(
+            b,
+            b'0'..=b'9' | b';' | b':' | b'?' | b'[' | b']' | b'(' | b')' | b'#'
+        )
+    })
+}
+
+/// A [`Write`] adapter that removes escape sequences before forwarding bytes.
+///
+/// Sequences split across `write` calls are recognised: only an unfinished
+/// tail such as `ESC [ 38 ; 5` is held back, and it is forwarded once the
+/// sequence completes or turns out not to be one. Matching follows
+/// [`strip_ansi_bytes`](crate::strip_ansi_bytes): raw 8-bit C1 controls are
+/// recognised, but not a 0x9B / 0x9C belonging to a UTF-8 character, so
+/// multibyte characters pass through intact even when split across writes.
+///
+/// Call [`finish`](StripWriter::finish) at the end of the stream to forward
+/// a held-back tail; dropping the writer discards it.
+#[derive(Debug)]
+pub struct StripWriter<W: Write> {
+    inner: W,
+    stripper: Stripper,
+    // Stripped output not yet accepted by `inner`, from `pos`.
+    out: Vec<u8>,
+    pos: usize,
+}
+
+impl<W: Write> StripWriter<W> {
+    /// Wrap `inner`, holding back at most [`DEFAULT_MAX_PENDING`] bytes.
+    pub fn new(inner: W) -> Self {
+        StripWriter::with_max_pending(inner, DEFAULT_MAX_PENDING)
+    }
+
+    /// Wrap `inner`, giving up on an unfinished sequence once it is longer
+    /// than `max_pending` bytes and forwarding it as text.
+    pub fn with_max_pending(inner: W, max_pending: usize) -> Self {
+        StripWriter {
+            inner,
+            stripper: Stripper::new(max_pending),
+            out: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Mutable reference to the wrapped writer. Writing to it directly
+    /// bypasses any held-back bytes.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Forward any held-back bytes, flush, and return the wrapped writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.drain()?;
+        self.stripper.finish(&mut self.out);
+        self.drain()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    // Forward the output of earlier writes.
+    fn drain(&mut self) -> io::Result<()> {
+        while self.pos < self.out.len() {
+            match self.inner.write(&self.out[self.pos..]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => self.pos += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.out.clear();
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for StripWriter<W> {
+    /// Forwards the output of earlier writes first; if the wrapped writer
+    /// fails then, nothing of `buf` is taken. Once `buf` is taken, whatever
+    /// the wrapped writer does not accept is kept and sent first by the next
+    /// `write` or `flush`, which report any error.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.drain()?;
+        self.stripper.push(buf, &mut self.out);
+        // `buf` is consumed, so a failure here must not be reported as if it
+        // were not; the next call retries the remaining output.
+        let _ = self.drain();
+        Ok(buf.len())
+    }
+
+    /// Forwards buffered output and flushes the wrapped writer. An unfinished
+    /// sequence stays held back because later writes may still complete it.
+    fn flush(&mut self) -> io::Result<()> {
+        self.drain()?;
+        self.inner.flush()
+    }
+}
+
+/// A [`Read`] / [`BufRead`] adapter yielding the wrapped reader's bytes with
+/// escape sequences removed.
+///
+/// Sequences straddling internal read boundaries are handled like in
+/// [`StripWriter`]. An OSC or other sequence that never terminates is held
+/// back until it exceeds the maximum pending length or the reader reaches end
+/// of input, and is then passed through as text.
+#[derive(Debug)]
+pub struct StripReader<R: Read> {
+    inner: R,
+    stripper: Stripper,
+    chunk: Vec<u8>,
+    out: Vec<u8>,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: Read> StripReader<R> {
+    /// Wrap `inner`, holding back at most [`DEFAULT_MAX_PENDING`] bytes.
+    pub fn new(inner: R) -> Self {
+        StripReader::with_max_pending(inner, DEFAULT_MAX_PENDING)
+    }
+
+    /// Wrap `inner`, giving up on an unfinished sequence once it is longer
+    /// than `max_pending` bytes and yielding it as text.
+    pub fn with_max_pending(inner: R, max_pending: usize) -> Self {
+        StripReader {
+            inner,
+            stripper: Stripper::new(max_pending),
+            chunk: vec![0; READ_CHUNK],
+            out: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Mutable reference to the wrapped reader. Reading from it directly
+    /// skips the stripper and desynchronises any held-back bytes.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Return the wrapped reader, discarding buffered and held-back bytes.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for StripReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read> BufRead for StripReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        // A chunk made entirely of escapes yields nothing, so keep reading.
+        while self.pos >= self.out.len() && !self.eof {
+            self.out.clear();
+            self.pos = 0;
+            let n = self.inner.read(&mut self.chunk)?;
+            if n == 0 {
+                self.eof = true;
+                self.stripper.finish(&mut self.out);
+            } else {
+                self.stripper.push(&self.chunk[..n], &mut self.out);
+            }
+        }
+        Ok(&self.out[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.out.len());
+    }
+}
@@ -5,6 +5,60 @@
 use regex::Regex;
 use std::sync::LazyLock;
 
+#[cfg(feature = "tokio")]
+mod async_io;
+mod builder;
+mod csi;
+mod html;
+mod hyperlink;
+mod io;
+mod parser;
+mod patterns;
+mod sanitize;
+mod sgr;
+mod slice;
+#[cfg(feature = "futures")]
+mod stream;
+mod strip;
+mod stripped;
+mod style;
+mod token;
+mod truncate;
+mod visualize;
+mod width;
+mod wrap;
+
+#[cfg(feature = "tokio")]
+pub use async_io::{AsyncStripReader, AsyncStripWriter};
+pub use builder::{AnsiRegex, AnsiRegexBuilder};
+pub use csi::{Csi, CsiParam};
+pub use html::{to_html, HtmlOptions, Rgb, XTERM_PALETTE};
+pub use hyperlink::{
+    hyperlink, hyperlinks, map_hyperlinks, remove_hyperlinks, Hyperlink, HyperlinkBuilder,
+    Hyperlinks, Terminator,
+};
+pub use io::{StripReader, StripWriter, DEFAULT_MAX_PENDING};
+pub use parser::{Event, Parser};
+pub use patterns::{
+    CHARSET_PATTERN, CONTROL_STRING_PATTERN, CSI_7BIT_PATTERN, CSI_8BIT_PATTERN,
+    CSI_STRICT_PATTERN, DEC_HASH_PATTERN, DSR_PATTERN, OSC_PATTERN, VT52_PATTERN,
+};
+pub use sanitize::{sanitize, Sanitizer};
+pub use sgr::{Color, Sgr, SgrAttr, UnderlineStyle};
+pub use slice::slice_visible;
+#[cfg(feature = "futures")]
+pub use stream::{strip_stream, token_stream, StreamToken, StripStream, TokenStream};
+pub use strip::{
+    strip_ansi, strip_ansi_bytes, strip_ansi_bytes_in_place, strip_ansi_cow, strip_ansi_in_place,
+};
+pub use stripped::{strip_ansi_mapped, StrippedText};
+pub use style::{styled_segments, Style, StyleState, StyledSegments};
+pub use token::{tokenize, AnsiTokenizer, EscapeKind, EscapeSequence, Token};
+pub use truncate::truncate_visible;
+pub use visualize::visualize;
+pub use width::{text_width, visible_width};
+pub use wrap::{wrap, WrapOptions};
+
 // Pattern derived to align with ansi-regex JS semantics (v6 era):
 // Matches:
 // 1. OSC: ESC ] ... (terminated by BEL | ESC \\ | 0x9C) – non-greedy.
@@ -12,7 +66,8 @@ use std::sync::LazyLock;
 // 3. Other 2-byte escape sequences used by some terminals.
 // This pattern intentionally does not attempt to validate every numeric range; it
 // mirrors practical coverage of color/style + link sequences.
-// The pattern is kept as a single constant string literal for compile-time embedding.
+// The pattern is kept as a single constant string literal for compile-time embedding;
+// its branches live in `patterns.rs` and are joined by `ansi_pattern!`.
 
 // Simplified & Rust-regex-compatible form (negated char class for OSC body):
 //  - OSC: ESC ] then any bytes except BEL, ESC, 0x9C lazily until a terminator BEL | ESC \ | 0x9C
@@ -29,18 +84,28 @@ use std::sync::LazyLock;
 // 4. Charset selection: ESC ( or ) then one of A B 0 1 2.
 // 5. DEC line/screen alignment etc with '#'.
 // These extra explicit branches ensure ESC A etc match while ESC ] (incomplete OSC) does not.
-pub const ANSI_REGEX_PATTERN: &str = concat!(
+pub const ANSI_REGEX_PATTERN: &str =
+    ansi_pattern!(osc, csi_7bit, csi_8bit, vt52, charset, dec_hash, dsr);
+
+// Byte-oriented counterpart of `ANSI_REGEX_PATTERN` for `regex::bytes`.
+// Unicode mode is disabled so `\x9B` / `\x9C` denote the raw 8-bit C1 bytes
+// emitted by 8-bit terminals, and the OSC body class accepts arbitrary bytes.
+// The optional `\xC2` lead byte keeps the UTF-8 encodings of U+009B / U+009C
+// matching as whole sequences. A raw 0x9B / 0x9C can also continue a UTF-8
+// character (`ě` is C4 9B); without look-behind the pattern cannot tell, so
+// `strip_ansi_bytes` checks the preceding bytes of each match.
+pub const ANSI_REGEX_BYTES_PATTERN: &str = concat!(
+    "(?-u)",
     // OSC branch
-    "(?:\\x1B\\][^\\x07\\x1B\\x9C]*?(?:\\x07|\\x1B\\\\|\\x9C))",
+    "(?:\\x1B\\][^\\x07\\x1B\\x9C]*?(?:\\x07|\\x1B\\\\|\\xC2?\\x9C))",
     "|",
     // CSI ESC[ ...
     "(?:\\x1B\\[[\\[\\]()#;?]*(?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)?[0-9A-PR-TZcf-nq-uy=><~])",
     "|",
     // CSI single-byte 0x9B ...
-    "(?:\\x9B[\\[\\]()#;?]*(?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)?[0-9A-PR-TZcf-nq-uy=><~])",
+    "(?:\\xC2?\\x9B[\\[\\]()#;?]*(?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)?[0-9A-PR-TZcf-nq-uy=><~])",
     "|",
     // VT52 / short escapes (single final)
-    // Added E (NEL), M (RI), c (reset), m (SGR reset), plus existing cursor & mode keys.
     "(?:\\x1B[ABCDHIKJSTZ=><sum78EMcNO])",
     "|",
     // Charset selection ESC (X or )X where X in A B 0 1 2
@@ -49,10 +114,37 @@ pub const ANSI_REGEX_PATTERN: &str = concat!(
     // Hash sequences ESC # 3 4 5 6 8
     "(?:\\x1B#[34568])",
     "|",
-    // Device status reports / queries: ESC [ 5 n etc (already covered by CSI) but bare 'ESC 5 n' appears in fixtures => add generic ESC [0-9]+[n] pattern fallback
+    // Bare ESC digits n fallback (see ANSI_REGEX_PATTERN)
     "(?:\\x1B[0-9]+n)"
 );
 
+// Opt-in superset of `ANSI_REGEX_PATTERN` that also matches the ECMA-48
+// control strings left out by upstream: DCS (`ESC P`), SOS (`ESC X`), PM
+// (`ESC ^`) and APC (`ESC _`), or their 8-bit forms 0x90 / 0x98 / 0x9E / 0x9F.
+// They are terminated by ST only (`ESC \` or 0x9C); BEL is not a terminator for
+// these families. A doubled ESC inside the body is kept as data, which covers
+// tmux passthrough (`ESC P tmux; ESC ESC ... ESC \`). Unterminated strings do
+// not match, as with OSC.
+pub const ANSI_REGEX_EXTENDED_PATTERN: &str = ansi_pattern!(
+    control_string,
+    osc,
+    csi_7bit,
+    csi_8bit,
+    vt52,
+    charset,
+    dec_hash,
+    dsr
+);
+
+// ECMA-48 exact variant of `ANSI_REGEX_PATTERN`: the CSI branches accept any
+// run of parameter bytes (0x30–0x3F), then intermediate bytes (0x20–0x2F), then
+// a single final byte (0x40–0x7E), so sequences such as DECSCUSR `ESC [ 2 SP q`,
+// ICH `ESC [ @` or `ESC [ 12345 ; 1 H` match whole. Non-standard forms that
+// upstream tolerates, like the Linux console's `ESC [ [ A`, end at the first
+// byte that is final per the standard. The other branches are unchanged.
+pub const ANSI_REGEX_STRICT_PATTERN: &str =
+    ansi_pattern!(osc, csi_strict, vt52, charset, dec_hash, dsr);
+
 static ANSI_REGEX_GLOBAL: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(ANSI_REGEX_PATTERN).expect("valid ANSI regex"));
 
@@ -60,16 +152,57 @@ static ANSI_REGEX_GLOBAL: LazyLock<Regex> =
 static ANSI_REGEX_FIRST: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(ANSI_REGEX_PATTERN).expect("valid ANSI regex"));
 
+static ANSI_REGEX_EXTENDED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(ANSI_REGEX_EXTENDED_PATTERN).expect("valid extended ANSI regex"));
+
+static ANSI_REGEX_STRICT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(ANSI_REGEX_STRICT_PATTERN).expect("valid strict ANSI regex"));
+
+static ANSI_REGEX_BYTES: LazyLock<regex::bytes::Regex> = LazyLock::new(|| {
+    regex::bytes::Regex::new(ANSI_REGEX_BYTES_PATTERN).expect("valid ANSI bytes regex")
+});
+
 /// Return the compiled global-style ANSI regex (intended for finding all matches).
 pub fn ansi_regex() -> &'static Regex {
     &ANSI_REGEX_GLOBAL
 }
 
 /// Return the compiled first-match ANSI regex (semantic helper; identical underlying pattern).
+///
+/// For real first-match behaviour use [`AnsiRegexBuilder::only_first`].
 pub fn ansi_regex_first() -> &'static Regex {
     &ANSI_REGEX_FIRST
 }
 
+/// Return the compiled regex for [`ANSI_REGEX_EXTENDED_PATTERN`].
+///
+/// Matches everything [`ansi_regex`] does plus DCS, SOS, PM and APC control
+/// strings (sixel images, kitty graphics, tmux passthrough). Tokenize with it
+/// through [`AnsiTokenizer::with_regex`].
+pub fn ansi_regex_extended() -> &'static Regex {
+    &ANSI_REGEX_EXTENDED
+}
+
+/// Return the compiled regex for [`ANSI_REGEX_STRICT_PATTERN`].
+///
+/// Identical to [`ansi_regex`] except that CSI sequences follow ECMA-48
+/// exactly: any parameter bytes, intermediate bytes and final byte.
+pub fn ansi_regex_strict() -> &'static Regex {
+    &ANSI_REGEX_STRICT
+}
+
+/// Return the compiled byte-oriented ANSI regex.
+///
+/// Matches the same sequences as [`ansi_regex`] but over `&[u8]`, treating 8-bit
+/// C1 introducers and terminators (0x9B, 0x9C) as raw bytes. Use it for PTY
+/// captures and logs that are not valid UTF-8. The regex alone also matches a
+/// 0x9B / 0x9C that is the continuation byte of a character such as `ě` or
+/// `Ü`; [`strip_ansi_bytes`] rules those out, and on UTF-8 text
+/// [`ansi_regex`] never sees them.
+pub fn ansi_regex_bytes() -> &'static regex::bytes::Regex {
+    &ANSI_REGEX_BYTES
+}
+
 /// Return the raw ANSI regex pattern string.
 pub fn pattern() -> &'static str {
     ANSI_REGEX_PATTERN
@@ -0,0 +1,315 @@
+//! Resumable push parser recognising the same sequences as the regex.
+
+use crate::strip::continues_char;
+use crate::{EscapeKind, DEFAULT_MAX_PENDING};
+
+/// Item produced by [`Parser`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<'a> {
+    /// Bytes outside any escape sequence (never empty).
+    Text(&'a [u8]),
+    /// A complete escape sequence and its category.
+    Escape(EscapeKind, &'a [u8]),
+}
+
+// States follow the shape of the DEC/VT500 diagram (ground, escape, CSI entry,
+// OSC string, ...), restricted to the language of `ANSI_REGEX_BYTES_PATTERN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum State {
+    #[default]
+    Ground,
+    // 0xC2 seen in ground: possibly the UTF-8 encoding of 0x9B.
+    Lead,
+    Escape,
+    Charset,
+    Hash,
+    DeviceStatus,
+    // Collecting a CSI body; `usize` is the introducer length.
+    Csi(usize),
+    OscString,
+    // 0x9C at this offset may end the OSC or continue a UTF-8 character that
+    // later bytes complete.
+    OscStringEnd(usize),
+    OscEscape,
+}
+
+/// Incremental escape sequence parser.
+///
+/// Bytes are pushed with [`feed`](Parser::feed) in chunks of any size, and
+/// events are emitted as soon as they are decided; an unfinished sequence is
+/// kept between calls. The recognised language and match boundaries are those
+/// of [`ansi_regex_bytes`](crate::ansi_regex_bytes), except that a 0x9B or
+/// 0x9C belonging to a UTF-8 character is text, as in
+/// [`strip_ansi_bytes`](crate::strip_ansi_bytes). The text events therefore
+/// concatenate to its output, and on UTF-8 input the escapes are exactly
+/// those [`AnsiTokenizer`](crate::AnsiTokenizer) yields, without needing the
+/// input up front.
+///
+/// An unfinished sequence is retained up to a maximum length
+/// ([`DEFAULT_MAX_PENDING`] by default); past it the sequence is given up on
+/// and emitted as text, so a stream that never terminates an OSC or CSI
+/// cannot grow the parser without bound.
+///
+/// ```
+/// use regex_ansi::{EscapeKind, Event, Parser};
+///
+/// let mut parser = Parser::new();
+/// let mut events = Vec::new();
+/// for chunk in [&b"ok \x1b[3"[..], b"2mgreen"] {
+///     parser.feed(chunk, |e| events.push(format!("{e:?}")));
+/// }
+/// assert_eq!(events.len(), 3);
+/// assert!(events[1].contains("Csi"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Parser {
+    state: State,
+    // Bytes of the sequence being recognised, from its introducer.
+    seq: Vec<u8>,
+    text: Vec<u8>,
+    // Up to three bytes scanned before the current position since the last
+    // match, to tell whether a 0x9B continues a UTF-8 character.
+    recent: Vec<u8>,
+    max_pending: usize,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser::with_max_pending(DEFAULT_MAX_PENDING)
+    }
+}
+
+impl Parser {
+    /// A parser in the ground state, retaining at most
+    /// [`DEFAULT_MAX_PENDING`] bytes of an unfinished sequence.
+    pub fn new() -> Self {
+        Parser::default()
+    }
+
+    /// A parser that gives up on an unfinished sequence once it is longer
+    /// than `max_pending` bytes and emits it as text.
+    pub fn with_max_pending(max_pending: usize) -> Self {
+        Parser {
+            state: State::Ground,
+            seq: Vec::new(),
+            text: Vec::new(),
+            recent: Vec::new(),
+            max_pending,
+        }
+    }
+
+    /// Bytes of an unfinished sequence carried over to the next call.
+    pub fn pending(&self) -> &[u8] {
+        &self.seq
+    }
+
+    /// Push `input`, calling `f` for every event it completes. Text is emitted
+    /// before returning; an unfinished sequence is retained.
+    pub fn feed(&mut self, input: &[u8], mut f: impl FnMut(Event<'_>)) {
+        for &b in input {
+            self.step(b, &mut f);
+        }
+        self.flush_text(&mut f);
+    }
+
+    /// Signal end of input: resolve any retained bytes the way the regex would
+    /// at the end of the haystack, and return to the ground state.
+    pub fn finish(&mut self, mut f: impl FnMut(Event<'_>)) {
+        while !self.seq.is_empty() {
+            match self.state {
+                State::Csi(intro) => match csi_match(&self.seq[intro..]) {
+                    Some(len) => self.accept(intro + len, EscapeKind::Csi, &mut f),
+                    None => self.reject(&mut f),
+                },
+                State::OscStringEnd(st) => self.accept(st + 1, EscapeKind::Osc, &mut f),
+                _ => self.reject(&mut f),
+            }
+        }
+        self.flush_text(&mut f);
+    }
+
+    fn step(&mut self, b: u8, f: &mut impl FnMut(Event<'_>)) {
+        if self.state == State::Ground {
+            match b {
+                0x1B => self.state = State::Escape,
+                0x9B if !self.continues_recent(b) => self.state = State::Csi(1),
+                0xC2 => self.state = State::Lead,
+                _ => return self.push_text(b),
+            }
+            return self.seq.push(b);
+        }
+        self.seq.push(b);
+        let len = self.seq.len();
+        match self.state {
+            State::Ground => unreachable!("handled above"),
+            State::Lead => match b {
+                0x9B => self.state = State::Csi(2),
+                _ => self.reject(f),
+            },
+            State::Escape => match b {
+                b']' => self.state = State::OscString,
+                b'[' => self.state = State::Csi(2),
+                b'(' | b')' => self.state = State::Charset,
+                b'#' => self.state = State::Hash,
+                // Listed before the digits: `ESC 7` / `ESC 8` are short escapes.
+                b'A' | b'B' | b'C' | b'D' | b'H' | b'I' | b'K' | b'J' | b'S' | b'T' | b'Z'
+                | b'=' | b'>' | b'<' | b's' | b'u' | b'm' | b'7' | b'8' | b'E' | b'M' | b'c'
+                | b'N' | b'O' => self.accept(len, EscapeKind::Short, f),
+                b'0'..=b'9' => self.state = State::DeviceStatus,
+                _ => self.reject(f),
+            },
+            State::Charset => match b {
+                b'A' | b'B' | b'0' | b'1' | b'2' => self.accept(len, EscapeKind::Charset, f),
+                _ => self.reject(f),
+            },
+            State::Hash => match b {
+                b'3' | b'4' | b'5' | b'6' | b'8' => self.accept(len, EscapeKind::DecHash, f),
+                _ => self.reject(f),
+            },
+            State::DeviceStatus => match b {
+                b'0'..=b'9' => {}
+                b'n' => self.accept(len, EscapeKind::DeviceStatus, f),
+                _ => self.reject(f),
+            },
+            State::Csi(intro) => {
+                // While every byte could still be prefix or parameter, a later
+                // byte may select a longer match; decide once that is ruled out.
+                if !is_csi_open(b) {
+                    match csi_match(&self.seq[intro..]) {
+                        Some(n) => self.accept(intro + n, EscapeKind::Csi, f),
+                        None => self.reject(f),
+                    }
+                }
+            }
+            State::OscString => match b {
+                0x07 => self.accept(len, EscapeKind::Osc, f),
+                0x9C => match continues_char(&self.seq, len - 1) {
+                    Some(true) => {}
+                    Some(false) => self.accept(len, EscapeKind::Osc, f),
+                    None => self.state = State::OscStringEnd(len - 1),
+                },
+                0x1B => self.state = State::OscEscape,
+                _ => {}
+            },
+            State::OscStringEnd(st) => match continues_char(&self.seq, st) {
+                Some(true) => self.state = State::OscString,
+                Some(false) => self.accept(st + 1, EscapeKind::Osc, f),
+                None => {}
+            },
+            State::OscEscape => match b {
+                b'\\' => self.accept(len, EscapeKind::Osc, f),
+                _ => self.reject(f),
+            },
+        }
+        if self.seq.len() > self.max_pending {
+            for b in std::mem::take(&mut self.seq) {
+                self.push_text(b);
+            }
+            self.state = State::Ground;
+        }
+    }
+
+    // Whether `b`, following the recently scanned bytes, certainly continues
+    // a UTF-8 character. If that is still open, later bytes decide: a CSI
+    // body byte rules the character out, a continuation byte rejects the CSI.
+    fn continues_recent(&self, b: u8) -> bool {
+        let mut buf = self.recent.clone();
+        buf.push(b);
+        continues_char(&buf, buf.len() - 1) == Some(true)
+    }
+
+    fn push_text(&mut self, b: u8) {
+        self.text.push(b);
+        if self.recent.len() == 3 {
+            self.recent.remove(0);
+        }
+        self.recent.push(b);
+    }
+
+    // Emit `seq[..len]` as an escape and re-scan whatever followed it.
+    fn accept(&mut self, len: usize, kind: EscapeKind, f: &mut impl FnMut(Event<'_>)) {
+        self.flush_text(f);
+        let seq = std::mem::take(&mut self.seq);
+        f(Event::Escape(kind, &seq[..len]));
+        // A match ends on an ASCII byte or a 0x9C that is no continuation,
+        // so no character can straddle it.
+        self.recent.clear();
+        self.state = State::Ground;
+        for &b in &seq[len..] {
+            self.step(b, f);
+        }
+    }
+
+    // No match starts at the introducer: it becomes text and the bytes after
+    // it are scanned again, as the regex would retry at the next position.
+    fn reject(&mut self, f: &mut impl FnMut(Event<'_>)) {
+        let seq = std::mem::take(&mut self.seq);
+        self.push_text(seq[0]);
+        self.state = State::Ground;
+        for &b in &seq[1..] {
+            self.step(b, f);
+        }
+    }
+
+    fn flush_text(&mut self, f: &mut impl FnMut(Event<'_>)) {
+        if !self.text.is_empty() {
+            f(Event::Text(&self.text));
+            self.text.clear();
+        }
+    }
+}
+
+// Bytes that can continue the prefix or parameter part of a CSI body.
+fn is_csi_open(b: u8) -> bool {
+    matches!(
+        b,
+        b'0'..=b'9' | b';' | b':' | b'?' | b'[' | b']' | b'(' | b')' | b'#'
+    )
+}
+
+fn is_csi_prefix(b: u8) -> bool {
+    matches!(b, b'[' | b']' | b'(' | b')' | b'#' | b';' | b'?')
+}
+
+fn is_csi_final(b: u8) -> bool {
+    matches!(b, b'0'..=b'9' | b'A'..=b'P' | b'R'..=b'T' | b'Z' | b'c' | b'f'..=b'n' | b'q'..=b'u' | b'y' | b'=' | b'>' | b'<' | b'~')
+}
+
+// Length of the CSI body (after the introducer) the upstream pattern
+//   [\[\]()#;?]* (?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)? [final]
+// matches under the regex's leftmost-first priorities, found in one scan:
+// - no prefix byte is a final byte, so only the longest prefix can match;
+// - the greedy path takes every `[;:]` group with as many digits as allowed
+//   and succeeds if a final byte follows;
+// - otherwise backtracking gives back the last digit taken, which is itself a
+//   final byte, so the match ends after the last group that has digits.
+fn csi_match(body: &[u8]) -> Option<usize> {
+    let mut i = body.iter().take_while(|&&b| is_csi_prefix(b)).count();
+    let digits = count_digits(body, i, 4);
+    if digits == 0 {
+        return csi_final(body, i);
+    }
+    i += digits;
+    let mut last_digit = i;
+    while matches!(body.get(i), Some(b';' | b':')) {
+        let digits = count_digits(body, i + 1, 4);
+        i += 1 + digits;
+        if digits > 0 {
+            last_digit = i;
+        }
+    }
+    csi_final(body, i).or(Some(last_digit))
+}
+
+fn csi_final(body: &[u8], i: usize) -> Option<usize> {
+    body.get(i).filter(|&&b| is_csi_final(b)).map(|_| i + 1)
+}
+
+fn count_digits(body: &[u8], i: usize, max: usize) -> usize {
+    body.get(i..)
+        .unwrap_or_default()
+        .iter()
+        .take(max)
+        .take_while(|b| b.is_ascii_digit())
+        .count()
+}
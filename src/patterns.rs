@@ -0,0 +1,87 @@
+//! Individual pattern branches and compile-time composition.
+//!
+//! `concat!` only accepts literals, so the branches are provided by the
+//! [`ansi_branch!`](crate::ansi_branch) macro and combined with
+//! [`ansi_pattern!`](crate::ansi_pattern); the constants below are the same
+//! literals for runtime use.
+
+/// Expand to the string literal of one pattern branch.
+///
+/// Branch names: `osc`, `csi_7bit`, `csi_8bit`, `vt52`, `charset`,
+/// `dec_hash`, `dsr`, plus `control_string` (DCS / SOS / PM / APC) and
+/// `csi_strict` from the extended and strict variants.
+#[macro_export]
+macro_rules! ansi_branch {
+    // OSC: ESC ] then any bytes except BEL, ESC, 0x9C lazily until a terminator BEL | ESC \ | 0x9C
+    (osc) => {
+        "(?:\\x1B\\][^\\x07\\x1B\\x9C]*?(?:\\x07|\\x1B\\\\|\\x9C))"
+    };
+    // CSI ESC[ ...
+    (csi_7bit) => {
+        "(?:\\x1B\\[[\\[\\]()#;?]*(?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)?[0-9A-PR-TZcf-nq-uy=><~])"
+    };
+    // CSI single-byte 0x9B ...
+    (csi_8bit) => {
+        "(?:\\x9B[\\[\\]()#;?]*(?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)?[0-9A-PR-TZcf-nq-uy=><~])"
+    };
+    // VT52 / short escapes (single final)
+    // Added E (NEL), M (RI), c (reset), m (SGR reset), plus existing cursor & mode keys.
+    (vt52) => {
+        "(?:\\x1B[ABCDHIKJSTZ=><sum78EMcNO])"
+    };
+    // Charset selection ESC (X or )X where X in A B 0 1 2
+    (charset) => {
+        "(?:\\x1B[()][AB012])"
+    };
+    // Hash sequences ESC # 3 4 5 6 8
+    (dec_hash) => {
+        "(?:\\x1B#[34568])"
+    };
+    // Device status reports / queries: ESC [ 5 n etc (already covered by CSI) but bare 'ESC 5 n' appears in fixtures => add generic ESC [0-9]+[n] pattern fallback
+    (dsr) => {
+        "(?:\\x1B[0-9]+n)"
+    };
+    // DCS / SOS / PM / APC, 7-bit or 8-bit introducer, ST terminated
+    (control_string) => {
+        "(?:(?:\\x1B[PX^_]|[\\x90\\x98\\x9E\\x9F])(?:[^\\x1B\\x9C]|\\x1B\\x1B)*?(?:\\x1B\\\\|\\x9C))"
+    };
+    // CSI ESC[ or 0x9B, parameter bytes, intermediate bytes, final byte
+    (csi_strict) => {
+        "(?:(?:\\x1B\\[|\\x9B)[\\x30-\\x3F]*[\\x20-\\x2F]*[\\x40-\\x7E])"
+    };
+}
+
+/// Expand to a `&'static str` literal joining the named branches with `|`,
+/// in the order given (earlier branches win at the same position).
+///
+/// ```
+/// const CSI_AND_OSC: &str = regex_ansi::ansi_pattern!(osc, csi_7bit, csi_8bit);
+///
+/// let re = regex::Regex::new(CSI_AND_OSC).unwrap();
+/// assert_eq!(re.replace_all("\x1b[1mhi\x1b(B", ""), "hi\x1b(B");
+/// ```
+#[macro_export]
+macro_rules! ansi_pattern {
+    ($first:ident $(, $rest:ident)* $(,)?) => {
+        concat!($crate::ansi_branch!($first) $(, "|", $crate::ansi_branch!($rest))*)
+    };
+}
+
+/// OSC branch: `ESC ] ... (BEL | ESC \ | 0x9C)`.
+pub const OSC_PATTERN: &str = ansi_branch!(osc);
+/// Upstream CSI branch with the `ESC [` introducer.
+pub const CSI_7BIT_PATTERN: &str = ansi_branch!(csi_7bit);
+/// Upstream CSI branch with the single-byte `0x9B` introducer.
+pub const CSI_8BIT_PATTERN: &str = ansi_branch!(csi_8bit);
+/// VT52 and other two-character escapes.
+pub const VT52_PATTERN: &str = ansi_branch!(vt52);
+/// Character set selection: `ESC ( X` / `ESC ) X`.
+pub const CHARSET_PATTERN: &str = ansi_branch!(charset);
+/// DEC line attributes and alignment: `ESC # n`.
+pub const DEC_HASH_PATTERN: &str = ansi_branch!(dec_hash);
+/// Bare device status fallback: `ESC <digits> n`.
+pub const DSR_PATTERN: &str = ansi_branch!(dsr);
+/// DCS, SOS, PM and APC control strings (extended pattern only).
+pub const CONTROL_STRING_PATTERN: &str = ansi_branch!(control_string);
+/// ECMA-48 CSI branch covering both introducers (strict pattern only).
+pub const CSI_STRICT_PATTERN: &str = ansi_branch!(csi_strict);
@@ -0,0 +1,207 @@
+//! Allow-list sanitizer for rendering untrusted text on a terminal.
+
+use crate::style::osc8_parts;
+use crate::{
+    AnsiRegex, AnsiRegexBuilder, AnsiTokenizer, EscapeKind, EscapeSequence, Sgr, StyleState, Token,
+};
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+// Every family, with CSI matched per ECMA-48 so that sequences the upstream
+// branch only partly covers (`ESC [ > 4 ; 2 m`) are judged as a whole.
+static SANITIZE_REGEX: LazyLock<AnsiRegex> = LazyLock::new(|| {
+    AnsiRegexBuilder::new()
+        .family(EscapeKind::Dcs, true)
+        .family(EscapeKind::Sos, true)
+        .family(EscapeKind::Pm, true)
+        .family(EscapeKind::Apc, true)
+        .strict_csi(true)
+        .build()
+        .expect("valid sanitizer regex")
+});
+
+/// Removes or escapes every escape sequence and control character that is
+/// not explicitly allowed.
+///
+/// By default SGR sequences (colors and text attributes) and OSC 8 hyperlinks
+/// to `http` / `https` URLs are kept, and everything else is removed: window
+/// title and clipboard OSCs, cursor movement, screen clears, device status
+/// queries that make the terminal reply, DCS / APC payloads, and stray
+/// controls such as a lone ESC, BEL, backspace or C1 characters. Tab, line
+/// feed and carriage return are kept.
+///
+/// Sequences are recognised with the branches of
+/// [`ansi_regex_extended`](crate::ansi_regex_extended), CSI following
+/// ECMA-48 as in [`ansi_regex_strict`](crate::ansi_regex_strict), and
+/// classified by [`EscapeKind`]. A style or link left open by the input is closed at the
+/// end so it cannot bleed into what is printed next.
+///
+/// ```
+/// use regex_ansi::Sanitizer;
+///
+/// let s = "\x1b]0;pwned\x07\x1b[31mred\x1b[2J";
+/// assert_eq!(Sanitizer::new().sanitize(s), "\x1b[31mred\x1b[0m");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sanitizer {
+    sgr: bool,
+    hyperlinks: bool,
+    schemes: Vec<String>,
+    families: Vec<EscapeKind>,
+    escape: bool,
+    close_open: bool,
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Sanitizer {
+            sgr: true,
+            hyperlinks: true,
+            schemes: vec!["http".to_owned(), "https".to_owned()],
+            families: Vec::new(),
+            escape: false,
+            close_open: true,
+        }
+    }
+}
+
+impl Sanitizer {
+    /// Keep SGR and `http` / `https` OSC 8 links, remove everything else.
+    pub fn new() -> Self {
+        Sanitizer::default()
+    }
+
+    /// Keep SGR sequences. Default `true`.
+    pub fn allow_sgr(mut self, allow: bool) -> Self {
+        self.sgr = allow;
+        self
+    }
+
+    /// Keep OSC 8 hyperlinks whose URL uses an allowed scheme, and every link
+    /// close. Default `true`.
+    pub fn allow_hyperlinks(mut self, allow: bool) -> Self {
+        self.hyperlinks = allow;
+        self
+    }
+
+    /// URL schemes accepted in hyperlinks, compared case-insensitively.
+    /// Default `http` and `https`.
+    pub fn link_schemes<I, S>(mut self, schemes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.schemes = schemes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Keep every sequence of a family verbatim, e.g. [`EscapeKind::Charset`].
+    pub fn allow_family(mut self, kind: EscapeKind) -> Self {
+        if !self.families.contains(&kind) {
+            self.families.push(kind);
+        }
+        self
+    }
+
+    /// Render rejected sequences and controls visibly instead of removing
+    /// them: C0 controls become Unicode control pictures (`ESC` is `␛`) and
+    /// C1 controls their 7-bit form (`U+009B` is `␛[`). Default `false`.
+    pub fn escape_disallowed(mut self, escape: bool) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Append the sequences that close a style or hyperlink the input left
+    /// open. Default `true`.
+    pub fn close_open(mut self, close: bool) -> Self {
+        self.close_open = close;
+        self
+    }
+
+    /// Whether `seq` passes the policy.
+    pub fn is_allowed(&self, seq: &EscapeSequence<'_>) -> bool {
+        if self.families.contains(&seq.kind()) {
+            return true;
+        }
+        match seq.kind() {
+            EscapeKind::Csi | EscapeKind::Short => self.sgr && Sgr::parse(seq.as_str()).is_some(),
+            EscapeKind::Osc => {
+                self.hyperlinks
+                    && osc8_parts(seq.as_str()).is_some_and(|(_, url)| self.allows_url(url))
+            }
+            _ => false,
+        }
+    }
+
+    // An empty URL closes a link and is always fine.
+    fn allows_url(&self, url: &str) -> bool {
+        if url.is_empty() {
+            return true;
+        }
+        if url.chars().any(char::is_control) {
+            return false;
+        }
+        url.split_once(':')
+            .is_some_and(|(scheme, _)| self.schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)))
+    }
+
+    /// Apply the policy to `s`, borrowing it when nothing had to change.
+    pub fn sanitize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let mut out = String::with_capacity(s.len());
+        let mut state = StyleState::new();
+        for token in AnsiTokenizer::with_regex(s, SANITIZE_REGEX.as_regex()) {
+            match token {
+                Token::Escape(seq) if self.is_allowed(&seq) => {
+                    out.push_str(seq.as_str());
+                    state.apply_escape(&seq);
+                }
+                Token::Escape(seq) => self.reject(seq.as_str(), &mut out),
+                Token::Text(text) => {
+                    for c in text.chars() {
+                        if c.is_control() && !matches!(c, '\t' | '\n' | '\r') {
+                            self.reject(c.encode_utf8(&mut [0; 4]), &mut out);
+                        } else {
+                            out.push(c);
+                        }
+                    }
+                }
+            }
+        }
+        if self.close_open {
+            out.push_str(&state.close_sequence());
+        }
+        if out == s {
+            Cow::Borrowed(s)
+        } else {
+            Cow::Owned(out)
+        }
+    }
+
+    fn reject(&self, raw: &str, out: &mut String) {
+        if self.escape {
+            out.push_str(&escape_controls(raw));
+        }
+    }
+}
+
+// Make every control character in `s` visible.
+fn escape_controls(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c as u32 {
+            n @ 0x00..=0x1F => out.push(char::from_u32(0x2400 + n).expect("control picture")),
+            0x7F => out.push('\u{2421}'),
+            n @ 0x80..=0x9F => {
+                out.push('\u{241B}');
+                out.push(char::from_u32(n - 0x40).expect("ASCII"));
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Shorthand for [`Sanitizer::new`]`.sanitize(s)`.
+pub fn sanitize(s: &str) -> Cow<'_, str> {
+    Sanitizer::new().sanitize(s)
+}
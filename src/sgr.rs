@@ -0,0 +1,262 @@
+//! Select Graphic Rendition (`CSI ... m`) decoding into typed attributes.
+
+use crate::{Csi, CsiParam};
+
+/// A terminal color as expressed by SGR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// One of the 16 palette colors: 0–7 standard (`30`–`37`), 8–15 bright (`90`–`97`).
+    Basic(u8),
+    /// 256-color palette index (`38;5;n`).
+    Indexed(u8),
+    /// 24-bit color (`38;2;r;g;b` or `38:2::r:g:b`).
+    Rgb(u8, u8, u8),
+}
+
+/// Underline shape selected by `4` / `21` / `4:n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnderlineStyle {
+    /// `4` or `4:1`.
+    Single,
+    /// `21` or `4:2`.
+    Double,
+    /// `4:3`.
+    Curly,
+    /// `4:4`.
+    Dotted,
+    /// `4:5`.
+    Dashed,
+}
+
+/// A single decoded SGR attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SgrAttr {
+    /// `0`, an empty parameter, `ESC [ m` or `ESC m`.
+    Reset,
+    /// `1`.
+    Bold,
+    /// `2`.
+    Dim,
+    /// `22`: neither bold nor dim.
+    NormalIntensity,
+    /// `3`.
+    Italic,
+    /// `23`.
+    NotItalic,
+    /// `4`, `21` or `4:1`–`4:5`.
+    Underline(UnderlineStyle),
+    /// `24` or `4:0`.
+    NoUnderline,
+    /// `5`.
+    Blink,
+    /// `6`.
+    RapidBlink,
+    /// `25`.
+    NoBlink,
+    /// `7`.
+    Inverse,
+    /// `27`.
+    NoInverse,
+    /// `8`.
+    Hidden,
+    /// `28`.
+    NoHidden,
+    /// `9`.
+    Strike,
+    /// `29`.
+    NoStrike,
+    /// `53`.
+    Overline,
+    /// `55`.
+    NoOverline,
+    /// `30`–`37`, `90`–`97` or `38;...`.
+    Foreground(Color),
+    /// `39`.
+    DefaultForeground,
+    /// `40`–`47`, `100`–`107` or `48;...`.
+    Background(Color),
+    /// `49`.
+    DefaultBackground,
+    /// `58;...`.
+    UnderlineColor(Color),
+    /// `59`.
+    DefaultUnderlineColor,
+    /// A parameter this decoder does not model (including malformed colors),
+    /// carrying its leading code.
+    Unknown(u32),
+}
+
+/// Decoded attributes of one SGR sequence, in order of appearance.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Sgr {
+    attrs: Vec<SgrAttr>,
+}
+
+impl Sgr {
+    /// Decode an SGR sequence: a CSI with final byte `m`, or the short `ESC m`
+    /// reset accepted by the upstream pattern.
+    pub fn parse(seq: &str) -> Option<Sgr> {
+        if seq == "\x1bm" {
+            return Some(Sgr {
+                attrs: vec![SgrAttr::Reset],
+            });
+        }
+        Sgr::from_csi(&Csi::parse(seq)?)
+    }
+
+    /// Decode an already parsed CSI; `None` unless it is a plain `m` sequence
+    /// without private marker or intermediates.
+    pub fn from_csi(csi: &Csi<'_>) -> Option<Sgr> {
+        if csi.final_byte() != 'm'
+            || csi.private_marker().is_some()
+            || !csi.intermediates().is_empty()
+        {
+            return None;
+        }
+        let params = csi.params();
+        if params.is_empty() {
+            return Some(Sgr {
+                attrs: vec![SgrAttr::Reset],
+            });
+        }
+        let mut attrs = Vec::with_capacity(params.len());
+        let mut i = 0;
+        while i < params.len() {
+            let (attr, used) = decode(params, i);
+            attrs.push(attr);
+            i += used;
+        }
+        Some(Sgr { attrs })
+    }
+
+    /// Decoded attributes.
+    pub fn attrs(&self) -> &[SgrAttr] {
+        &self.attrs
+    }
+}
+
+impl IntoIterator for Sgr {
+    type Item = SgrAttr;
+    type IntoIter = std::vec::IntoIter<SgrAttr>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.attrs.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s Sgr {
+    type Item = &'s SgrAttr;
+    type IntoIter = std::slice::Iter<'s, SgrAttr>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.attrs.iter()
+    }
+}
+
+// Decode the attribute starting at `params[i]`, returning it with the number of
+// `;` parameters consumed (more than one only for semicolon color forms).
+fn decode(params: &[CsiParam], i: usize) -> (SgrAttr, usize) {
+    let p = &params[i];
+    let code = p.value_or(0);
+    let attr = match code {
+        0 => SgrAttr::Reset,
+        1 => SgrAttr::Bold,
+        2 => SgrAttr::Dim,
+        3 => SgrAttr::Italic,
+        4 => match p.subparams().first() {
+            None => SgrAttr::Underline(UnderlineStyle::Single),
+            Some(style) => match style.unwrap_or(0) {
+                0 => SgrAttr::NoUnderline,
+                1 => SgrAttr::Underline(UnderlineStyle::Single),
+                2 => SgrAttr::Underline(UnderlineStyle::Double),
+                3 => SgrAttr::Underline(UnderlineStyle::Curly),
+                4 => SgrAttr::Underline(UnderlineStyle::Dotted),
+                5 => SgrAttr::Underline(UnderlineStyle::Dashed),
+                _ => SgrAttr::Unknown(4),
+            },
+        },
+        5 => SgrAttr::Blink,
+        6 => SgrAttr::RapidBlink,
+        7 => SgrAttr::Inverse,
+        8 => SgrAttr::Hidden,
+        9 => SgrAttr::Strike,
+        21 => SgrAttr::Underline(UnderlineStyle::Double),
+        22 => SgrAttr::NormalIntensity,
+        23 => SgrAttr::NotItalic,
+        24 => SgrAttr::NoUnderline,
+        25 => SgrAttr::NoBlink,
+        27 => SgrAttr::NoInverse,
+        28 => SgrAttr::NoHidden,
+        29 => SgrAttr::NoStrike,
+        30..=37 => SgrAttr::Foreground(Color::Basic((code - 30) as u8)),
+        39 => SgrAttr::DefaultForeground,
+        40..=47 => SgrAttr::Background(Color::Basic((code - 40) as u8)),
+        49 => SgrAttr::DefaultBackground,
+        53 => SgrAttr::Overline,
+        55 => SgrAttr::NoOverline,
+        59 => SgrAttr::DefaultUnderlineColor,
+        90..=97 => SgrAttr::Foreground(Color::Basic((code - 90 + 8) as u8)),
+        100..=107 => SgrAttr::Background(Color::Basic((code - 100 + 8) as u8)),
+        38 | 48 | 58 => {
+            let (color, used) = if p.has_subparams() {
+                (colon_color(p.subparams()), 1)
+            } else {
+                semicolon_color(&params[i + 1..])
+            };
+            let attr = match (code, color) {
+                (38, Some(c)) => SgrAttr::Foreground(c),
+                (48, Some(c)) => SgrAttr::Background(c),
+                (58, Some(c)) => SgrAttr::UnderlineColor(c),
+                _ => SgrAttr::Unknown(code),
+            };
+            return (attr, used);
+        }
+        _ => SgrAttr::Unknown(code),
+    };
+    (attr, 1)
+}
+
+// `38:5:n`, `38:2:cs:r:g:b` or the common `38:2:r:g:b` without color space id.
+fn colon_color(sub: &[Option<u32>]) -> Option<Color> {
+    match sub.first().copied().flatten()? {
+        5 => Some(Color::Indexed(channel(sub.get(1).copied()?)?)),
+        2 => {
+            let rgb = match sub.len() {
+                4 => &sub[1..4],
+                n if n >= 5 => &sub[2..5],
+                _ => return None,
+            };
+            Some(Color::Rgb(
+                channel(rgb[0])?,
+                channel(rgb[1])?,
+                channel(rgb[2])?,
+            ))
+        }
+        _ => None,
+    }
+}
+
+// `38;5;n` or `38;2;r;g;b`; returns the parameters consumed including `38`.
+fn semicolon_color(rest: &[CsiParam]) -> (Option<Color>, usize) {
+    let channel_at = |i: usize| rest.get(i).and_then(|p| channel(p.value()));
+    match rest.first().map(CsiParam::value) {
+        None => (None, 1),
+        Some(Some(5)) if rest.len() >= 2 => (channel_at(1).map(Color::Indexed), 3),
+        Some(Some(2)) if rest.len() >= 4 => {
+            let rgb = match (channel_at(1), channel_at(2), channel_at(3)) {
+                (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r, g, b)),
+                _ => None,
+            };
+            (rgb, 5)
+        }
+        // Truncated or unsupported color space: skip what is left of it.
+        Some(Some(2 | 5)) => (None, rest.len() + 1),
+        Some(_) => (None, 2),
+    }
+}
+
+// Empty fields count as 0; values above 255 are rejected.
+fn channel(v: Option<u32>) -> Option<u8> {
+    u8::try_from(v.unwrap_or(0)).ok()
+}
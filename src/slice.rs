@@ -0,0 +1,72 @@
+//! Slicing by visible column range with the escape state replayed.
+
+use crate::width::grapheme_width;
+use crate::{AnsiTokenizer, StyleState, Token};
+use std::ops::{Bound, RangeBounds};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Return the part of `s` covering the visible columns in `range`.
+///
+/// The SGR style and OSC 8 hyperlink active at the start of the range are
+/// re-emitted first, escape sequences inside the range are kept, and the
+/// result ends by closing whatever is still open. A wide character that
+/// straddles either edge of the range is left out.
+///
+/// ```
+/// use regex_ansi::slice_visible;
+///
+/// let s = "\x1b[31mhello world\x1b[0m";
+/// assert_eq!(slice_visible(s, 6..), "\x1b[31mworld\x1b[0m");
+/// ```
+pub fn slice_visible(s: &str, range: impl RangeBounds<usize>) -> String {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n.saturating_add(1),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => usize::MAX,
+    };
+    let mut out = String::new();
+    if start >= end {
+        return out;
+    }
+    let mut state = StyleState::new();
+    let mut started = false;
+    let mut col = 0;
+    'tokens: for token in AnsiTokenizer::new(s) {
+        match token {
+            Token::Escape(seq) => {
+                state.apply_escape(&seq);
+                if started {
+                    out.push_str(seq.as_str());
+                }
+            }
+            Token::Text(text) => {
+                for g in text.graphemes(true) {
+                    let w = grapheme_width(g);
+                    if col + w > end {
+                        break 'tokens;
+                    }
+                    if col >= start {
+                        if !started {
+                            started = true;
+                            out.push_str(&state.open_sequence());
+                        }
+                        out.push_str(g);
+                    }
+                    col += w;
+                    if col >= end {
+                        break 'tokens;
+                    }
+                }
+            }
+        }
+    }
+    if started {
+        out.push_str(&state.close_sequence());
+    }
+    out
+}
@@ -0,0 +1,157 @@
+//! `futures` stream adapters over chunked bytes.
+
+use crate::io::Stripper;
+use crate::{EscapeKind, Event, Parser, DEFAULT_MAX_PENDING};
+use bytes::Bytes;
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+/// Owned token yielded by [`TokenStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamToken {
+    /// Text between escape sequences. A run of text may arrive split over
+    /// several tokens, following the input chunks.
+    Text(Bytes),
+    /// A complete escape sequence and its category.
+    Escape(EscapeKind, Bytes),
+}
+
+pin_project! {
+    /// Stream of input chunks with escape sequences removed.
+    ///
+    /// Created by [`strip_stream`]. Sequences split across chunks are
+    /// recognised the same way as by [`StripReader`](crate::StripReader);
+    /// chunks that strip to nothing are skipped.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct StripStream<S> {
+        #[pin]
+        inner: S,
+        stripper: Stripper,
+        done: bool,
+    }
+}
+
+impl<S: Stream<Item = Bytes>> StripStream<S> {
+    /// Wrap `inner`, giving up on an unfinished sequence once it is longer
+    /// than `max_pending` bytes and yielding it as text.
+    pub fn with_max_pending(inner: S, max_pending: usize) -> Self {
+        StripStream {
+            inner,
+            stripper: Stripper::new(max_pending),
+            done: false,
+        }
+    }
+
+    /// Return the wrapped stream, discarding held-back bytes.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Stream<Item = Bytes>> Stream for StripStream<S> {
+    type Item = Bytes;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Bytes>> {
+        let mut this = self.project();
+        while !*this.done {
+            let mut out = Vec::new();
+            match ready!(this.inner.as_mut().poll_next(cx)) {
+                Some(chunk) => this.stripper.push(&chunk, &mut out),
+                None => {
+                    *this.done = true;
+                    this.stripper.finish(&mut out);
+                }
+            }
+            if !out.is_empty() {
+                return Poll::Ready(Some(out.into()));
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+/// Strip escape sequences from a stream of byte chunks, holding back at most
+/// [`DEFAULT_MAX_PENDING`] bytes of an unfinished sequence.
+pub fn strip_stream<S: Stream<Item = Bytes>>(inner: S) -> StripStream<S> {
+    StripStream::with_max_pending(inner, DEFAULT_MAX_PENDING)
+}
+
+pin_project! {
+    /// Stream of [`StreamToken`]s decoded from input chunks.
+    ///
+    /// Created by [`token_stream`]. Decoding uses [`Parser`], so sequences
+    /// split across chunks are yielded whole, and the text tokens carry
+    /// exactly what [`strip_stream`] would yield for the same input. An
+    /// unfinished sequence longer than the maximum pending length is yielded
+    /// as text.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct TokenStream<S> {
+        #[pin]
+        inner: S,
+        parser: Parser,
+        ready: VecDeque<StreamToken>,
+        done: bool,
+    }
+}
+
+impl<S: Stream<Item = Bytes>> TokenStream<S> {
+    /// Wrap `inner`, giving up on an unfinished sequence once it is longer
+    /// than `max_pending` bytes and yielding it as text.
+    pub fn with_max_pending(inner: S, max_pending: usize) -> Self {
+        TokenStream {
+            inner,
+            parser: Parser::with_max_pending(max_pending),
+            ready: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Return the wrapped stream, discarding undelivered tokens and
+    /// held-back bytes.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Stream<Item = Bytes>> Stream for TokenStream<S> {
+    type Item = StreamToken;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamToken>> {
+        let mut this = self.project();
+        loop {
+            if let Some(token) = this.ready.pop_front() {
+                return Poll::Ready(Some(token));
+            }
+            if *this.done {
+                return Poll::Ready(None);
+            }
+            let ready = &mut *this.ready;
+            let push = |e: Event<'_>| {
+                ready.push_back(match e {
+                    Event::Text(text) => StreamToken::Text(Bytes::copy_from_slice(text)),
+                    Event::Escape(kind, seq) => {
+                        StreamToken::Escape(kind, Bytes::copy_from_slice(seq))
+                    }
+                })
+            };
+            match ready!(this.inner.as_mut().poll_next(cx)) {
+                Some(chunk) => this.parser.feed(&chunk, push),
+                None => {
+                    *this.done = true;
+                    this.parser.finish(push);
+                }
+            }
+        }
+    }
+}
+
+/// Decode a stream of byte chunks into text and escape tokens, holding back
+/// at most [`DEFAULT_MAX_PENDING`] bytes of an unfinished sequence.
+pub fn token_stream<S: Stream<Item = Bytes>>(inner: S) -> TokenStream<S> {
+    TokenStream::with_max_pending(inner, DEFAULT_MAX_PENDING)
+}
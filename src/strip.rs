@@ -0,0 +1,168 @@
+//! Allocation-aware stripping helpers built on [`ansi_regex`](crate::ansi_regex).
+
+use crate::{ansi_regex, ansi_regex_bytes, ANSI_REGEX_PATTERN};
+use regex::bytes::Regex as BytesRegex;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+const ESC: u8 = 0x1B;
+const C1_CSI: u8 = 0x9B;
+const C1_ST: u8 = 0x9C;
+
+// Same pattern as `ansi_regex()`, compiled for byte haystacks so in-place
+// compaction of a `String` can search a buffer whose prefix has already been
+// rewritten.
+pub(crate) static ANSI_REGEX_UTF8_BYTES: LazyLock<BytesRegex> =
+    LazyLock::new(|| BytesRegex::new(ANSI_REGEX_PATTERN).expect("valid ANSI regex"));
+
+/// Remove every ANSI escape sequence from `s`.
+///
+/// Returns the input borrowed when nothing matched, so the common case of
+/// plain text never allocates.
+pub fn strip_ansi(s: &str) -> Cow<'_, str> {
+    ansi_regex().replace_all(s, "")
+}
+
+/// Like [`strip_ansi`] but accepts an existing `Cow`, reusing an owned buffer
+/// instead of allocating a second one.
+pub fn strip_ansi_cow(s: Cow<'_, str>) -> Cow<'_, str> {
+    match s {
+        Cow::Borrowed(b) => strip_ansi(b),
+        Cow::Owned(mut o) => {
+            strip_ansi_in_place(&mut o);
+            Cow::Owned(o)
+        }
+    }
+}
+
+/// Remove every ANSI escape sequence from `s` without reallocating it.
+pub fn strip_ansi_in_place(s: &mut String) {
+    if !ansi_regex().is_match(s) {
+        return;
+    }
+    let mut bytes = std::mem::take(s).into_bytes();
+    compact(&mut bytes, |buf, from| {
+        ANSI_REGEX_UTF8_BYTES.find_at(buf, from).map(|m| m.range())
+    });
+    // Whole matches are removed, and every match starts and ends on a char
+    // boundary, so the remaining bytes are still valid UTF-8.
+    *s = String::from_utf8(bytes).expect("stripping preserves UTF-8");
+}
+
+/// Remove every ANSI escape sequence from a byte slice using
+/// [`ansi_regex_bytes`], borrowing the input when nothing matched.
+///
+/// Raw 8-bit C1 sequences are removed, except that a 0x9B or 0x9C byte
+/// belonging to a well-formed UTF-8 character (`ě` is `C4 9B`, `Ü` is
+/// `C3 9C`) is text, not a C1 control, so UTF-8 input is stripped exactly as
+/// [`strip_ansi`] would strip it.
+pub fn strip_ansi_bytes(s: &[u8]) -> Cow<'_, [u8]> {
+    let mut out = Vec::new();
+    let mut last = 0;
+    while let Some(m) = find_c1_at(s, last) {
+        out.extend_from_slice(&s[last..m.start]);
+        last = m.end;
+    }
+    if last == 0 {
+        return Cow::Borrowed(s);
+    }
+    out.extend_from_slice(&s[last..]);
+    Cow::Owned(out)
+}
+
+/// Remove every ANSI escape sequence from a byte buffer without reallocating
+/// it, matching exactly what [`strip_ansi_bytes`] removes.
+pub fn strip_ansi_bytes_in_place(buf: &mut Vec<u8>) {
+    compact(buf, |buf, from| {
+        // Bytes before `from` may already have been overwritten, so the
+        // search must not look behind it; see `find_c1_at`.
+        find_c1_at(&buf[from..], 0).map(|m| m.start + from..m.end + from)
+    });
+}
+
+// Next match of `ansi_regex_bytes` at or after `from` that does not take a
+// UTF-8 continuation byte for a C1 control. The regex cannot look behind a
+// match, so a CSI starting at such a 0x9B is skipped, and an OSC body is
+// re-scanned so that such a 0x9C does not end it.
+//
+// Only the three bytes before a candidate are looked at. When `from` is the
+// end of an earlier match they may as well be ignored: a match ends on an
+// ASCII byte or on a 0x9C that does not continue a character, so no
+// character straddles `from`.
+pub(crate) fn find_c1_at(buf: &[u8], from: usize) -> Option<Range<usize>> {
+    let mut pos = from;
+    while let Some(m) = ansi_regex_bytes().find_at(buf, pos) {
+        let end = match buf[m.start()] {
+            C1_CSI if continues_char(buf, m.start()) == Some(true) => None,
+            ESC if buf[m.start() + 1] == b']' => osc_end(buf, m.start() + 2),
+            _ => Some(m.end()),
+        };
+        match end {
+            Some(end) => return Some(m.start()..end),
+            None => pos = m.start() + 1,
+        }
+    }
+    None
+}
+
+// End of the OSC whose body starts at `i`: after BEL, `ESC \` or a 0x9C that
+// is not part of a UTF-8 character other than U+009C itself.
+fn osc_end(buf: &[u8], mut i: usize) -> Option<usize> {
+    while i < buf.len() {
+        match buf[i] {
+            0x07 => return Some(i + 1),
+            ESC => return (buf.get(i + 1) == Some(&b'\\')).then_some(i + 2),
+            C1_ST if continues_char(buf, i) != Some(true) => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+// Whether the byte at `i` continues a well-formed UTF-8 character that starts
+// in the three bytes before it, or `None` if `buf` ends before that character
+// would, so that later bytes decide. A `C2` lead is excluded: `C2 9B` /
+// `C2 9C` are the C1 controls themselves.
+pub(crate) fn continues_char(buf: &[u8], i: usize) -> Option<bool> {
+    let mut decided = Some(false);
+    for back in 1..=i.min(3) {
+        let start = i - back;
+        let len = match buf[start] {
+            0xC3..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => continue,
+        };
+        if len <= back {
+            continue;
+        }
+        let end = buf.len().min(start + len);
+        match std::str::from_utf8(&buf[start..end]) {
+            Ok(_) => return Some(true),
+            Err(e) if e.error_len().is_none() => decided = None,
+            Err(_) => {}
+        }
+    }
+    decided
+}
+
+// Shift the bytes between matches down over the matched spans, then truncate.
+// Searching the whole buffer is sound as long as `find_at` never reads before
+// the search position, where bytes may already have been rewritten.
+fn compact(buf: &mut Vec<u8>, find_at: impl Fn(&[u8], usize) -> Option<Range<usize>>) {
+    let mut read = 0;
+    let mut write = 0;
+    while let Some(Range { start, end }) = find_at(buf, read) {
+        buf.copy_within(read..start, write);
+        write += start - read;
+        read = end;
+    }
+    if read == 0 {
+        return;
+    }
+    let len = buf.len();
+    buf.copy_within(read..len, write);
+    write += len - read;
+    buf.truncate(write);
+}
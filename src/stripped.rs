@@ -0,0 +1,171 @@
+//! Stripped text with an offset index back into the original string.
+
+use crate::ansi_regex;
+use crate::width::grapheme_width;
+use std::fmt;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Escapes removed at one stripped offset; adjacent sequences are merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Gap {
+    stripped: usize,
+    original: Range<usize>,
+}
+
+// A grapheme whose byte length and column width are not both one. Between two
+// of these every byte is exactly one column, so offsets interpolate linearly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    byte: usize,
+    col: usize,
+    len: usize,
+    width: usize,
+}
+
+/// Plain text produced by stripping escape sequences, together with a compact
+/// index that maps stripped byte offsets and visible columns back to offsets
+/// in the original string and vice versa.
+///
+/// The index stores one entry per run of removed sequences and one per
+/// grapheme that is not a single-byte, single-column character, so plain
+/// ASCII costs nothing beyond the stripped string itself.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StrippedText {
+    text: String,
+    gaps: Vec<Gap>,
+    cells: Vec<Cell>,
+}
+
+impl StrippedText {
+    /// Strip `original` with [`ansi_regex`] and index the removed spans.
+    pub fn new(original: &str) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut gaps: Vec<Gap> = Vec::new();
+        let mut last = 0;
+        for m in ansi_regex().find_iter(original) {
+            text.push_str(&original[last..m.start()]);
+            last = m.end();
+            match gaps.last_mut() {
+                Some(g) if g.original.end == m.start() => g.original.end = m.end(),
+                _ => gaps.push(Gap {
+                    stripped: text.len(),
+                    original: m.range(),
+                }),
+            }
+        }
+        text.push_str(&original[last..]);
+
+        let mut cells = Vec::new();
+        let mut col = 0;
+        for (byte, g) in text.grapheme_indices(true) {
+            let width = grapheme_width(g);
+            if g.len() != 1 || width != 1 {
+                cells.push(Cell {
+                    byte,
+                    col,
+                    len: g.len(),
+                    width,
+                });
+            }
+            col += width;
+        }
+        StrippedText { text, gaps, cells }
+    }
+
+    /// The stripped text.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Consume the index, keeping only the stripped text.
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// Visible width of the stripped text.
+    pub fn width(&self) -> usize {
+        self.column_of(self.text.len())
+    }
+
+    /// Map a stripped byte offset to the original string. When escapes were
+    /// removed at that point the offset after them is returned, i.e. the
+    /// position of the next visible character.
+    pub fn to_original(&self, offset: usize) -> usize {
+        let i = self.gaps.partition_point(|g| g.stripped <= offset);
+        match i.checked_sub(1).map(|i| &self.gaps[i]) {
+            Some(g) => g.original.end + (offset - g.stripped),
+            None => offset,
+        }
+    }
+
+    /// Map a stripped byte range to the original string. The start skips
+    /// escapes removed at its position while the end stops before them, so a
+    /// highlight never swallows neighbouring sequences.
+    pub fn to_original_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.to_original(range.start);
+        let i = self.gaps.partition_point(|g| g.stripped < range.end);
+        let end = match i.checked_sub(1).map(|i| &self.gaps[i]) {
+            Some(g) => g.original.end + (range.end - g.stripped),
+            None => range.end,
+        };
+        start..end.max(start)
+    }
+
+    /// Map an original byte offset into the stripped text. Offsets inside a
+    /// removed sequence map to where it was removed.
+    pub fn to_stripped(&self, offset: usize) -> usize {
+        let i = self.gaps.partition_point(|g| g.original.start <= offset);
+        match i.checked_sub(1).map(|i| &self.gaps[i]) {
+            Some(g) if offset < g.original.end => g.stripped,
+            Some(g) => g.stripped + (offset - g.original.end),
+            None => offset,
+        }
+    }
+
+    /// Map an original byte range into the stripped text.
+    pub fn to_stripped_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.to_stripped(range.start);
+        start..self.to_stripped(range.end).max(start)
+    }
+
+    /// Visible column at which the grapheme containing stripped byte `offset`
+    /// starts; `width()` at the end of the text.
+    pub fn column_of(&self, offset: usize) -> usize {
+        let i = self.cells.partition_point(|c| c.byte <= offset);
+        match i.checked_sub(1).map(|i| &self.cells[i]) {
+            Some(c) if offset < c.byte + c.len => c.col,
+            Some(c) => c.col + c.width + (offset - c.byte - c.len),
+            None => offset,
+        }
+    }
+
+    /// Stripped byte offset of the grapheme covering visible column `col`,
+    /// clamped to the end of the text.
+    pub fn offset_of_column(&self, col: usize) -> usize {
+        let i = self.cells.partition_point(|c| c.col <= col);
+        let offset = match i.checked_sub(1).map(|i| &self.cells[i]) {
+            Some(c) if col < c.col + c.width => c.byte,
+            Some(c) => c.byte + c.len + (col - c.col - c.width),
+            None => col,
+        };
+        offset.min(self.text.len())
+    }
+}
+
+impl AsRef<str> for StrippedText {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for StrippedText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Strip `s` and keep an index mapping stripped offsets back into it.
+pub fn strip_ansi_mapped(s: &str) -> StrippedText {
+    StrippedText::new(s)
+}
@@ -0,0 +1,288 @@
+//! Cumulative text style tracking across SGR sequences.
+
+use crate::{
+    AnsiTokenizer, Color, EscapeKind, EscapeSequence, Sgr, SgrAttr, Token, UnderlineStyle,
+};
+use std::fmt::Write as _;
+
+/// Effective graphic rendition at some point in the text.
+///
+/// `Style::default()` is the terminal's initial state. New attributes may be
+/// added, so build styles by changing fields of a default one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Style {
+    /// Bold or increased intensity (`1`); cleared with dim by `22`.
+    pub bold: bool,
+    /// Faint or decreased intensity (`2`); cleared with bold by `22`.
+    pub dim: bool,
+    /// Italic (`3`).
+    pub italic: bool,
+    /// Underline and its shape (`4`, `4:n`, `21`); `None` when off.
+    pub underline: Option<UnderlineStyle>,
+    /// Set by both slow (`5`) and rapid (`6`) blink.
+    pub blink: bool,
+    /// Foreground and background swapped (`7`).
+    pub inverse: bool,
+    /// Concealed text (`8`).
+    pub hidden: bool,
+    /// Crossed out (`9`).
+    pub strike: bool,
+    /// Overlined (`53`).
+    pub overline: bool,
+    /// Text color (`30`–`37`, `90`–`97`, `38`); `None` for the terminal default.
+    pub foreground: Option<Color>,
+    /// Background color (`40`–`47`, `100`–`107`, `48`); `None` for the
+    /// terminal default.
+    pub background: Option<Color>,
+    /// Underline color (`58`); `None` to follow the text color.
+    pub underline_color: Option<Color>,
+}
+
+impl Style {
+    /// Whether this is the terminal's initial style.
+    pub fn is_default(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// Apply a single attribute on top of this style.
+    pub fn apply(&mut self, attr: SgrAttr) {
+        match attr {
+            SgrAttr::Reset => *self = Style::default(),
+            SgrAttr::Bold => self.bold = true,
+            SgrAttr::Dim => self.dim = true,
+            SgrAttr::NormalIntensity => {
+                self.bold = false;
+                self.dim = false;
+            }
+            SgrAttr::Italic => self.italic = true,
+            SgrAttr::NotItalic => self.italic = false,
+            SgrAttr::Underline(u) => self.underline = Some(u),
+            SgrAttr::NoUnderline => self.underline = None,
+            SgrAttr::Blink | SgrAttr::RapidBlink => self.blink = true,
+            SgrAttr::NoBlink => self.blink = false,
+            SgrAttr::Inverse => self.inverse = true,
+            SgrAttr::NoInverse => self.inverse = false,
+            SgrAttr::Hidden => self.hidden = true,
+            SgrAttr::NoHidden => self.hidden = false,
+            SgrAttr::Strike => self.strike = true,
+            SgrAttr::NoStrike => self.strike = false,
+            SgrAttr::Overline => self.overline = true,
+            SgrAttr::NoOverline => self.overline = false,
+            SgrAttr::Foreground(c) => self.foreground = Some(c),
+            SgrAttr::DefaultForeground => self.foreground = None,
+            SgrAttr::Background(c) => self.background = Some(c),
+            SgrAttr::DefaultBackground => self.background = None,
+            SgrAttr::UnderlineColor(c) => self.underline_color = Some(c),
+            SgrAttr::DefaultUnderlineColor => self.underline_color = None,
+            SgrAttr::Unknown(_) => {}
+        }
+    }
+
+    /// A single SGR sequence that establishes this style from the default
+    /// state, or an empty string for the default style.
+    pub fn to_sgr(&self) -> String {
+        let mut params = String::new();
+        let mut push = |p: &str| {
+            if !params.is_empty() {
+                params.push(';');
+            }
+            params.push_str(p);
+        };
+        for (on, code) in [(self.bold, "1"), (self.dim, "2"), (self.italic, "3")] {
+            if on {
+                push(code);
+            }
+        }
+        match self.underline {
+            None => {}
+            Some(UnderlineStyle::Single) => push("4"),
+            Some(UnderlineStyle::Double) => push("4:2"),
+            Some(UnderlineStyle::Curly) => push("4:3"),
+            Some(UnderlineStyle::Dotted) => push("4:4"),
+            Some(UnderlineStyle::Dashed) => push("4:5"),
+        }
+        for (on, code) in [
+            (self.blink, "5"),
+            (self.inverse, "7"),
+            (self.hidden, "8"),
+            (self.strike, "9"),
+            (self.overline, "53"),
+        ] {
+            if on {
+                push(code);
+            }
+        }
+        if let Some(c) = self.foreground {
+            push(&color_params(c, 30, 90, 38));
+        }
+        if let Some(c) = self.background {
+            push(&color_params(c, 40, 100, 48));
+        }
+        if let Some(c) = self.underline_color {
+            // There is no 16-color underline form; use the palette index.
+            let c = match c {
+                Color::Basic(n) => Color::Indexed(n),
+                other => other,
+            };
+            push(&color_params(c, 0, 0, 58));
+        }
+        if params.is_empty() {
+            params
+        } else {
+            format!("\x1b[{params}m")
+        }
+    }
+}
+
+fn color_params(color: Color, base: u8, bright: u8, extended: u8) -> String {
+    let mut out = String::new();
+    match color {
+        Color::Basic(n) if n < 8 => write!(out, "{}", base + n),
+        Color::Basic(n) => write!(out, "{}", bright + (n & 7)),
+        Color::Indexed(n) => write!(out, "{extended};5;{n}"),
+        Color::Rgb(r, g, b) => write!(out, "{extended};2;{r};{g};{b}"),
+    }
+    .expect("writing to a String cannot fail");
+    out
+}
+
+/// Tracks the effective [`Style`] and open OSC 8 hyperlink while walking
+/// through escape sequences.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyleState {
+    style: Style,
+    // Raw OSC 8 sequence that opened the current link.
+    hyperlink: Option<String>,
+}
+
+impl StyleState {
+    /// Start from the default style.
+    pub fn new() -> Self {
+        StyleState::default()
+    }
+
+    /// Current effective style.
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Raw OSC 8 sequence that opened the hyperlink in effect, if any.
+    pub fn hyperlink(&self) -> Option<&str> {
+        self.hyperlink.as_deref()
+    }
+
+    /// Return to the initial state: default style and no open hyperlink.
+    pub fn reset(&mut self) {
+        self.style = Style::default();
+        self.hyperlink = None;
+    }
+
+    /// Sequences that re-establish this state on a fresh line: the style's SGR
+    /// followed by the open hyperlink. Empty in the initial state.
+    pub fn open_sequence(&self) -> String {
+        let mut out = self.style.to_sgr();
+        if let Some(link) = &self.hyperlink {
+            out.push_str(link);
+        }
+        out
+    }
+
+    /// Sequences that return the terminal to the initial state: an OSC 8 close
+    /// using the opener's terminator, then an SGR reset. Empty in the initial
+    /// state.
+    pub fn close_sequence(&self) -> String {
+        let mut out = String::new();
+        if let Some(link) = &self.hyperlink {
+            out.push_str("\x1b]8;;");
+            out.push_str(osc_terminator(link));
+        }
+        if !self.style.is_default() {
+            out.push_str("\x1b[0m");
+        }
+        out
+    }
+
+    /// Update the state from a token; text tokens leave it unchanged.
+    pub fn feed(&mut self, token: &Token<'_>) {
+        if let Token::Escape(seq) = token {
+            self.apply_escape(seq);
+        }
+    }
+
+    /// Update the state from an escape sequence.
+    ///
+    /// SGR sequences (including `ESC m`) are applied, OSC 8 opens or closes the
+    /// hyperlink and a full terminal reset (`ESC c`) restores the initial state;
+    /// everything else is ignored.
+    pub fn apply_escape(&mut self, seq: &EscapeSequence<'_>) {
+        match seq.kind() {
+            EscapeKind::Osc => {
+                if let Some((_, url)) = osc8_parts(seq.as_str()) {
+                    self.hyperlink = (!url.is_empty()).then(|| seq.as_str().to_owned());
+                }
+            }
+            EscapeKind::Short if seq.as_str() == "\x1bc" => self.reset(),
+            EscapeKind::Short | EscapeKind::Csi => {
+                if let Some(sgr) = Sgr::parse(seq.as_str()) {
+                    self.apply_sgr(&sgr);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply every attribute of a decoded SGR sequence in order.
+    pub fn apply_sgr(&mut self, sgr: &Sgr) {
+        for &attr in sgr {
+            self.style.apply(attr);
+        }
+    }
+}
+
+// Parameters and URI of an OSC 8 sequence (`ESC ] 8 ; params ; uri ST`); the
+// URI is empty for a close.
+pub(crate) fn osc8_parts(seq: &str) -> Option<(&str, &str)> {
+    let body = seq.strip_prefix("\x1b]8;")?;
+    let body = body.strip_suffix(osc_terminator(seq))?;
+    body.split_once(';')
+}
+
+pub(crate) fn osc_terminator(seq: &str) -> &'static str {
+    if seq.ends_with('\x07') {
+        "\x07"
+    } else if seq.ends_with('\u{9C}') {
+        "\u{9C}"
+    } else {
+        "\x1b\\"
+    }
+}
+
+/// Iterator over the text runs of a string paired with their effective style.
+#[derive(Debug, Clone)]
+pub struct StyledSegments<'a> {
+    tokens: AnsiTokenizer<'a>,
+    state: StyleState,
+}
+
+impl<'a> Iterator for StyledSegments<'a> {
+    type Item = (Style, &'a str);
+
+    fn next(&mut self) -> Option<(Style, &'a str)> {
+        for token in self.tokens.by_ref() {
+            match token {
+                Token::Text(text) => return Some((self.state.style().clone(), text)),
+                Token::Escape(seq) => self.state.apply_escape(&seq),
+            }
+        }
+        None
+    }
+}
+
+/// Split `s` into text runs, each with the style in effect where it appears.
+pub fn styled_segments(s: &str) -> StyledSegments<'_> {
+    StyledSegments {
+        tokens: AnsiTokenizer::new(s),
+        state: StyleState::new(),
+    }
+}
@@ -0,0 +1,186 @@
+//! Typed token stream over plain text and the escape sequences matched by
+//! [`ansi_regex`](crate::ansi_regex).
+
+use crate::{ansi_regex, Csi};
+use regex::Regex;
+
+/// Category of an escape sequence, mirroring the branches of
+/// [`ANSI_REGEX_PATTERN`](crate::ANSI_REGEX_PATTERN).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EscapeKind {
+    /// Operating System Command: `ESC ] ... BEL` / `ESC ] ... ESC \` / `ESC ] ... 0x9C`.
+    Osc,
+    /// Control Sequence Introducer: `ESC [ ...` or the single byte `0x9B ...`.
+    Csi,
+    /// Character set designation: `ESC ( B`, `ESC ) 0`, ...
+    Charset,
+    /// DEC line attributes and alignment test: `ESC # 8`, ...
+    DecHash,
+    /// VT52 and other two-byte escapes: `ESC A`, `ESC 7`, `ESC c`, `ESC m`, ...
+    Short,
+    /// Bare device status report form `ESC 5 n` accepted by upstream fixtures.
+    DeviceStatus,
+    /// Device Control String: `ESC P ... ST` or `0x90 ... ST`. Only matched by
+    /// [`ansi_regex_extended`](crate::ansi_regex_extended).
+    Dcs,
+    /// Start of String: `ESC X ... ST` or `0x98 ... ST` (extended pattern only).
+    Sos,
+    /// Privacy Message: `ESC ^ ... ST` or `0x9E ... ST` (extended pattern only).
+    Pm,
+    /// Application Program Command: `ESC _ ... ST` or `0x9F ... ST`, as used by
+    /// kitty graphics (extended pattern only).
+    Apc,
+}
+
+impl EscapeKind {
+    /// Classify a complete sequence as matched by the ANSI regex or its
+    /// extended variant.
+    ///
+    /// The result is unspecified for input that is not a full match.
+    pub fn of(seq: &str) -> EscapeKind {
+        let mut chars = seq.chars();
+        match chars.next() {
+            Some('\u{9B}') => return EscapeKind::Csi,
+            Some('\u{90}') => return EscapeKind::Dcs,
+            Some('\u{98}') => return EscapeKind::Sos,
+            Some('\u{9E}') => return EscapeKind::Pm,
+            Some('\u{9F}') => return EscapeKind::Apc,
+            _ => {}
+        }
+        match chars.next() {
+            Some(']') => EscapeKind::Osc,
+            Some('[') => EscapeKind::Csi,
+            Some('P') => EscapeKind::Dcs,
+            Some('X') => EscapeKind::Sos,
+            Some('^') => EscapeKind::Pm,
+            Some('_') => EscapeKind::Apc,
+            Some('(' | ')') => EscapeKind::Charset,
+            Some('#') => EscapeKind::DecHash,
+            // ESC 7 / ESC 8 belong to the short branch; longer digit runs are `ESC <n> n`.
+            Some('0'..='9') if seq.len() > 2 => EscapeKind::DeviceStatus,
+            _ => EscapeKind::Short,
+        }
+    }
+}
+
+/// A single escape sequence found in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscapeSequence<'a> {
+    kind: EscapeKind,
+    text: &'a str,
+}
+
+impl<'a> EscapeSequence<'a> {
+    /// Wrap a complete sequence matched by the ANSI regex, classifying it.
+    pub fn new(text: &'a str) -> Self {
+        EscapeSequence {
+            kind: EscapeKind::of(text),
+            text,
+        }
+    }
+
+    /// Category of this sequence.
+    pub fn kind(&self) -> EscapeKind {
+        self.kind
+    }
+
+    /// Raw text of the sequence, introducer and terminator included.
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Parsed form of a CSI sequence; `None` for other kinds.
+    pub fn csi(&self) -> Option<Csi<'a>> {
+        match self.kind {
+            EscapeKind::Csi => Csi::parse(self.text),
+            _ => None,
+        }
+    }
+}
+
+/// Item yielded by [`AnsiTokenizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// A run of text between escape sequences (never empty).
+    Text(&'a str),
+    /// An escape sequence.
+    Escape(EscapeSequence<'a>),
+}
+
+impl<'a> Token<'a> {
+    /// Raw text covered by this token.
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            Token::Text(t) => t,
+            Token::Escape(e) => e.as_str(),
+        }
+    }
+}
+
+/// Iterator splitting a string into [`Token`]s.
+///
+/// Concatenating the raw text of every token reproduces the input exactly.
+#[derive(Debug, Clone)]
+pub struct AnsiTokenizer<'a> {
+    input: &'a str,
+    pos: usize,
+    re: &'a Regex,
+    next_match: Option<(usize, usize)>,
+}
+
+impl<'a> AnsiTokenizer<'a> {
+    /// Tokenize `input` using [`ansi_regex`].
+    pub fn new(input: &'a str) -> Self {
+        AnsiTokenizer::with_regex(input, ansi_regex())
+    }
+
+    /// Tokenize `input` using another pattern, such as
+    /// [`ansi_regex_extended`](crate::ansi_regex_extended). Matches are
+    /// classified with [`EscapeKind::of`].
+    pub fn with_regex(input: &'a str, re: &'a Regex) -> Self {
+        AnsiTokenizer {
+            input,
+            pos: 0,
+            re,
+            next_match: None,
+        }
+    }
+
+    /// Byte offset of the next token within the input.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a> Iterator for AnsiTokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        if self.pos >= self.input.len() {
+            return None;
+        }
+        let (start, end) = match self.next_match {
+            Some(m) => m,
+            None => match self.re.find_at(self.input, self.pos) {
+                Some(m) => (m.start(), m.end()),
+                None => (self.input.len(), self.input.len()),
+            },
+        };
+        if start > self.pos {
+            // Remember the upcoming match so it is not searched for twice.
+            self.next_match = Some((start, end));
+            let text = &self.input[self.pos..start];
+            self.pos = start;
+            return Some(Token::Text(text));
+        }
+        self.next_match = None;
+        self.pos = end;
+        Some(Token::Escape(EscapeSequence::new(&self.input[start..end])))
+    }
+}
+
+/// Shorthand for [`AnsiTokenizer::new`].
+pub fn tokenize(input: &str) -> AnsiTokenizer<'_> {
+    AnsiTokenizer::new(input)
+}
@@ -0,0 +1,59 @@
+//! Column-based truncation that keeps escape sequences intact.
+
+use crate::width::grapheme_width;
+use crate::{text_width, visible_width, AnsiTokenizer, StyleState, Token};
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Shorten `s` to at most `max_cols` visible columns, ending with `ellipsis`.
+///
+/// Cuts only between grapheme clusters and never inside a sequence matched by
+/// [`ansi_regex`](crate::ansi_regex). Sequences before the cut are kept, the
+/// ellipsis is written in the style active at the cut, and the result ends
+/// by closing any open OSC 8 hyperlink and resetting SGR attributes, so the
+/// style does not leak past the truncated text. Input that already fits is
+/// returned unchanged.
+pub fn truncate_visible<'a>(s: &'a str, max_cols: usize, ellipsis: &str) -> Cow<'a, str> {
+    if visible_width(s) <= max_cols {
+        return Cow::Borrowed(s);
+    }
+    let ellipsis = fit(ellipsis, max_cols);
+    let budget = max_cols - text_width(ellipsis);
+
+    let mut out = String::with_capacity(s.len());
+    let mut state = StyleState::new();
+    let mut used = 0;
+    'tokens: for token in AnsiTokenizer::new(s) {
+        match token {
+            Token::Escape(seq) => {
+                state.apply_escape(&seq);
+                out.push_str(seq.as_str());
+            }
+            Token::Text(text) => {
+                for g in text.graphemes(true) {
+                    let w = grapheme_width(g);
+                    if used + w > budget {
+                        break 'tokens;
+                    }
+                    used += w;
+                    out.push_str(g);
+                }
+            }
+        }
+    }
+    out.push_str(ellipsis);
+    out.push_str(&state.close_sequence());
+    Cow::Owned(out)
+}
+
+// Longest prefix of plain `s` that fits in `cols` columns.
+fn fit(s: &str, cols: usize) -> &str {
+    let mut used = 0;
+    for (i, g) in s.grapheme_indices(true) {
+        used += grapheme_width(g);
+        if used > cols {
+            return &s[..i];
+        }
+    }
+    s
+}
@@ -0,0 +1,117 @@
+//! Readable rendering of escape sequences and control characters.
+
+use crate::style::osc_terminator;
+use crate::{AnsiTokenizer, EscapeKind, EscapeSequence, Token};
+
+const C0_NAMES: [&str; 32] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR",
+    "SO", "SI", "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC",
+    "FS", "GS", "RS", "US",
+];
+
+const C1_NAMES: [&str; 32] = [
+    "PAD", "HOP", "BPH", "NBH", "IND", "NEL", "SSA", "ESA", "HTS", "HTJ", "VTS", "PLD", "PLU",
+    "RI", "SS2", "SS3", "DCS", "PU1", "PU2", "STS", "CCH", "MW", "SPA", "EPA", "SOS", "SGCI",
+    "SCI", "CSI", "ST", "OSC", "PM", "APC",
+];
+
+/// Render `s` with every escape sequence matched by
+/// [`ansi_regex`](crate::ansi_regex) and every other control character made
+/// visible, for test failures and debugging.
+///
+/// Sequences become `<CSI 31 m>`, `<OSC 8;;url BEL>` (terminators `BEL`,
+/// `ST` or `C1 ST`), `<ESC 7>`, `<ESC ( B>`; the 8-bit CSI introducer is
+/// shown as `C1 CSI`. Stray controls become their names, such as `<ESC>`,
+/// `<BEL>` or `<NEL>`. Line feeds and tabs are kept as they are.
+///
+/// ```
+/// use regex_ansi::visualize;
+///
+/// assert_eq!(visualize("\x1b[1;31mhi\x1b[m\x07"), "<CSI 1;31 m>hi<CSI m><BEL>");
+/// ```
+pub fn visualize(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() * 2);
+    for token in AnsiTokenizer::new(s) {
+        match token {
+            Token::Text(text) => push_controls(text, &mut out),
+            Token::Escape(seq) => push_escape(&seq, &mut out),
+        }
+    }
+    out
+}
+
+fn push_escape(seq: &EscapeSequence<'_>, out: &mut String) {
+    let raw = seq.as_str();
+    match seq.kind() {
+        EscapeKind::Csi => {
+            let (name, body) = match raw.strip_prefix('\u{9B}') {
+                Some(body) => ("C1 CSI", body),
+                None => ("CSI", &raw[2..]),
+            };
+            let final_at = body.len() - 1;
+            out.push('<');
+            out.push_str(name);
+            if final_at > 0 {
+                out.push(' ');
+                push_controls(&body[..final_at], out);
+            }
+            out.push(' ');
+            out.push_str(&body[final_at..]);
+            out.push('>');
+        }
+        EscapeKind::Osc => {
+            let terminator = osc_terminator(raw);
+            let name = match terminator {
+                "\x07" => "BEL",
+                "\x1b\\" => "ST",
+                _ => "C1 ST",
+            };
+            out.push_str("<OSC ");
+            push_controls(&raw[2..raw.len() - terminator.len()], out);
+            out.push(' ');
+            out.push_str(name);
+            out.push('>');
+        }
+        EscapeKind::Charset | EscapeKind::DecHash => {
+            out.push_str("<ESC ");
+            out.push_str(&raw[1..2]);
+            out.push(' ');
+            out.push_str(&raw[2..]);
+            out.push('>');
+        }
+        EscapeKind::DeviceStatus => {
+            out.push_str("<ESC ");
+            out.push_str(&raw[1..raw.len() - 1]);
+            out.push_str(" n>");
+        }
+        EscapeKind::Short => {
+            out.push_str("<ESC ");
+            out.push_str(&raw[1..]);
+            out.push('>');
+        }
+        // Not produced by `ansi_regex`; show the raw text with visible controls.
+        EscapeKind::Dcs | EscapeKind::Sos | EscapeKind::Pm | EscapeKind::Apc => {
+            push_controls(raw, out)
+        }
+    }
+}
+
+fn push_controls(text: &str, out: &mut String) {
+    for c in text.chars() {
+        let name = match c as u32 {
+            0x0A | 0x09 => None,
+            n @ 0x00..=0x1F => Some(C0_NAMES[n as usize]),
+            0x7F => Some("DEL"),
+            n @ 0x80..=0x9F => Some(C1_NAMES[n as usize - 0x80]),
+            _ => None,
+        };
+        match name {
+            Some(name) => {
+                out.push('<');
+                out.push_str(name);
+                out.push('>');
+            }
+            None => out.push(c),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Display width of text containing escape sequences.
+
+use crate::{AnsiTokenizer, Token};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Number of terminal columns `s` occupies once every sequence matched by
+/// [`ansi_regex`](crate::ansi_regex) is ignored.
+///
+/// Text is measured per extended grapheme cluster: wide East Asian characters
+/// and emoji presentation sequences (ZWJ sequences, flags, `U+FE0F`) take two
+/// columns, combining marks and zero-width joiners take none. Control
+/// characters, including tab and newline, are counted as zero columns.
+pub fn visible_width(s: &str) -> usize {
+    AnsiTokenizer::new(s)
+        .map(|t| match t {
+            Token::Text(text) => text_width(text),
+            Token::Escape(_) => 0,
+        })
+        .sum()
+}
+
+/// Width of plain text without looking for escape sequences, using the same
+/// rules as [`visible_width`].
+pub fn text_width(s: &str) -> usize {
+    s.graphemes(true).map(grapheme_width).sum()
+}
+
+/// Width of a single extended grapheme cluster, at most two columns.
+pub(crate) fn grapheme_width(g: &str) -> usize {
+    if g.starts_with(char::is_control) {
+        return 0;
+    }
+    g.width().min(2)
+}
@@ -0,0 +1,271 @@
+//! Word wrapping on visible columns with styles carried across lines.
+
+use crate::width::grapheme_width;
+use crate::{text_width, AnsiTokenizer, EscapeSequence, StyleState, Token};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Options for [`wrap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrapOptions {
+    hard: bool,
+    trim: bool,
+    initial_indent: String,
+    subsequent_indent: String,
+}
+
+impl Default for WrapOptions {
+    fn default() -> Self {
+        WrapOptions {
+            hard: false,
+            trim: true,
+            initial_indent: String::new(),
+            subsequent_indent: String::new(),
+        }
+    }
+}
+
+impl WrapOptions {
+    /// Soft wrapping, trimmed whitespace, no indentation.
+    pub fn new() -> Self {
+        WrapOptions::default()
+    }
+
+    /// Break words longer than a line at the column limit instead of letting
+    /// them overflow. Default `false`.
+    pub fn hard(mut self, hard: bool) -> Self {
+        self.hard = hard;
+        self
+    }
+
+    /// Drop whitespace at the start and end of every line. Default `true`.
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Prefix for the first line, counted toward the width.
+    pub fn initial_indent(mut self, indent: impl Into<String>) -> Self {
+        self.initial_indent = indent.into();
+        self
+    }
+
+    /// Prefix for every line after the first, counted toward the width.
+    pub fn subsequent_indent(mut self, indent: impl Into<String>) -> Self {
+        self.subsequent_indent = indent.into();
+        self
+    }
+}
+
+/// Wrap `s` into lines of at most `width` visible columns.
+///
+/// Words are separated by whitespace and existing newlines are kept. Escape
+/// sequences stay attached to the text around them; every line re-opens the
+/// SGR style and OSC 8 hyperlink active at its start and closes them at its
+/// end, so each line renders correctly on its own.
+pub fn wrap(s: &str, width: usize, options: &WrapOptions) -> Vec<String> {
+    let mut w = Wrapper::new(width, options);
+    // Whitespace and escapes since the last word, then the word being built.
+    let mut gap: Vec<Piece<'_>> = Vec::new();
+    let mut word: Vec<Piece<'_>> = Vec::new();
+    let mut word_width = 0;
+    for piece in pieces(s) {
+        match piece {
+            Piece::Space(_) => {
+                if !word.is_empty() {
+                    w.place(&gap, &word, word_width);
+                    gap.clear();
+                    word.clear();
+                    word_width = 0;
+                }
+                gap.push(piece);
+            }
+            Piece::Newline => {
+                w.place(&gap, &word, word_width);
+                gap.clear();
+                word.clear();
+                word_width = 0;
+                w.break_line();
+            }
+            Piece::Glyph(_, gw) => {
+                word_width += gw;
+                word.push(piece);
+            }
+            Piece::Esc(_) if word.is_empty() => gap.push(piece),
+            Piece::Esc(_) => word.push(piece),
+        }
+    }
+    w.place(&gap, &word, word_width);
+    w.finish()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Piece<'a> {
+    Esc(EscapeSequence<'a>),
+    Space(&'a str),
+    Glyph(&'a str, usize),
+    Newline,
+}
+
+fn pieces(s: &str) -> impl Iterator<Item = Piece<'_>> {
+    AnsiTokenizer::new(s).flat_map(|token| {
+        let (esc, text) = match token {
+            Token::Escape(seq) => (Some(Piece::Esc(seq)), ""),
+            Token::Text(text) => (None, text),
+        };
+        esc.into_iter().chain(text.graphemes(true).map(|g| {
+            if g == "\n" || g == "\r\n" {
+                Piece::Newline
+            } else if g.chars().all(char::is_whitespace) {
+                Piece::Space(g)
+            } else {
+                Piece::Glyph(g, grapheme_width(g))
+            }
+        }))
+    })
+}
+
+struct Wrapper<'o> {
+    options: &'o WrapOptions,
+    width: usize,
+    lines: Vec<String>,
+    line: String,
+    line_width: usize,
+    // A glyph has been placed on the current line.
+    has_content: bool,
+    // The state replay has been written to the current line.
+    opened: bool,
+    state: StyleState,
+}
+
+impl<'o> Wrapper<'o> {
+    fn new(width: usize, options: &'o WrapOptions) -> Self {
+        Wrapper {
+            options,
+            width,
+            lines: Vec::new(),
+            line: options.initial_indent.clone(),
+            line_width: text_width(&options.initial_indent),
+            has_content: false,
+            opened: false,
+            state: StyleState::new(),
+        }
+    }
+
+    fn room(&self) -> usize {
+        self.width.saturating_sub(self.line_width)
+    }
+
+    // Place a word with the whitespace and escapes that preceded it. An empty
+    // word marks the end of a paragraph.
+    fn place(&mut self, gap: &[Piece<'_>], word: &[Piece<'_>], word_width: usize) {
+        let trim = self.options.trim;
+        if word.is_empty() {
+            self.push_gap(gap, !trim);
+            return;
+        }
+        let gap_width: usize = gap
+            .iter()
+            .map(|p| match p {
+                Piece::Space(sp) => grapheme_width(sp),
+                _ => 0,
+            })
+            .sum();
+        if self.has_content && gap_width + word_width > self.room() {
+            let fresh_room = self
+                .width
+                .saturating_sub(text_width(&self.options.subsequent_indent));
+            if self.options.hard && word_width > fresh_room && gap_width < self.room() {
+                // Too long for any line: start it here rather than on a new one.
+                self.push_gap(gap, true);
+                self.push_hard(word);
+                return;
+            }
+            self.push_gap(gap, !trim);
+            self.break_line();
+        } else {
+            self.push_gap(gap, self.has_content || !trim);
+        }
+        if self.options.hard && word_width > self.room() {
+            self.push_hard(word);
+        } else {
+            self.push_all(word);
+        }
+    }
+
+    // Escapes are always kept; whitespace only when asked to and while it fits.
+    fn push_gap(&mut self, gap: &[Piece<'_>], keep_spaces: bool) {
+        for &piece in gap {
+            if let Piece::Space(sp) = piece {
+                if !keep_spaces || (self.has_content && grapheme_width(sp) > self.room()) {
+                    continue;
+                }
+            }
+            self.push(piece);
+        }
+    }
+
+    fn push_all(&mut self, word: &[Piece<'_>]) {
+        for &piece in word {
+            self.push(piece);
+        }
+    }
+
+    // Push a word, breaking the line whenever the next glyph would overflow.
+    fn push_hard(&mut self, word: &[Piece<'_>]) {
+        for &piece in word {
+            if let Piece::Glyph(_, w) = piece {
+                if self.has_content && w > self.room() {
+                    self.break_line();
+                }
+            }
+            self.push(piece);
+        }
+    }
+
+    fn push(&mut self, piece: Piece<'_>) {
+        self.open();
+        match piece {
+            Piece::Esc(seq) => {
+                self.line.push_str(seq.as_str());
+                self.state.apply_escape(&seq);
+            }
+            Piece::Glyph(g, w) => {
+                self.line.push_str(g);
+                self.line_width += w;
+                self.has_content = true;
+            }
+            Piece::Space(sp) => {
+                self.line.push_str(sp);
+                self.line_width += grapheme_width(sp);
+            }
+            Piece::Newline => unreachable!("newlines end the line before reaching push"),
+        }
+    }
+
+    fn open(&mut self) {
+        if !self.opened {
+            self.opened = true;
+            self.line.push_str(&self.state.open_sequence());
+        }
+    }
+
+    fn break_line(&mut self) {
+        if self.opened {
+            self.line.push_str(&self.state.close_sequence());
+        }
+        let indent = &self.options.subsequent_indent;
+        self.lines
+            .push(std::mem::replace(&mut self.line, indent.clone()));
+        self.line_width = text_width(indent);
+        self.has_content = false;
+        self.opened = false;
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if self.opened {
+            self.line.push_str(&self.state.close_sequence());
+        }
+        self.lines.push(self.line);
+        self.lines
+    }
+}
@@ -0,0 +1,130 @@
+//! Options builder: default parity, families, C1, strict CSI and OSC limits.
+
+use regex_ansi::{
+    AnsiRegexBuilder, EscapeKind, ANSI_REGEX_EXTENDED_PATTERN, ANSI_REGEX_PATTERN,
+    ANSI_REGEX_STRICT_PATTERN,
+};
+use std::sync::Mutex;
+
+// Held by tests that rely on what the shared compile cache holds, so the
+// eviction test cannot flush it between their builds.
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn presets_reproduce_published_patterns() {
+    assert_eq!(AnsiRegexBuilder::new().pattern(), ANSI_REGEX_PATTERN);
+    assert_eq!(
+        AnsiRegexBuilder::new().strict_csi(true).pattern(),
+        ANSI_REGEX_STRICT_PATTERN
+    );
+    let extended = [
+        EscapeKind::Dcs,
+        EscapeKind::Sos,
+        EscapeKind::Pm,
+        EscapeKind::Apc,
+    ]
+    .into_iter()
+    .fold(AnsiRegexBuilder::new(), |b, k| b.family(k, true));
+    assert_eq!(extended.pattern(), ANSI_REGEX_EXTENDED_PATTERN);
+}
+
+#[test]
+fn build_is_cached() {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let a = AnsiRegexBuilder::new().c1(false).build().unwrap();
+    let b = AnsiRegexBuilder::new()
+        .c1(false)
+        .only_first(true)
+        .build()
+        .unwrap();
+    assert!(std::ptr::eq(a.as_regex(), b.as_regex()));
+    assert!(!a.is_only_first() && b.is_only_first());
+}
+
+#[test]
+fn only_first() {
+    let s = "\x1b[1ma\x1b[0mb";
+    let all = AnsiRegexBuilder::new().build().unwrap();
+    let first = AnsiRegexBuilder::new().only_first(true).build().unwrap();
+    assert_eq!(all.strip(s), "ab");
+    assert_eq!(first.strip(s), "a\x1b[0mb");
+    assert_eq!(all.find_iter(s).count(), 2);
+    assert_eq!(first.find_iter(s).count(), 1);
+    assert_eq!(first.replace(s, "$0"), "$0a\x1b[0mb");
+}
+
+#[test]
+fn family_selection() {
+    let s = "\x1b[31mred\x1b]0;title\x07\x1b(B\x1b#8\x1b7\x1b5n\x1b_Gi=1\x1b\\";
+    let csi_only = AnsiRegexBuilder::new()
+        .families([EscapeKind::Csi])
+        .build()
+        .unwrap();
+    assert_eq!(
+        csi_only.strip(s),
+        "red\x1b]0;title\x07\x1b(B\x1b#8\x1b7\x1b5n\x1b_Gi=1\x1b\\"
+    );
+    let no_osc = AnsiRegexBuilder::new()
+        .family(EscapeKind::Osc, false)
+        .family(EscapeKind::Apc, true)
+        .build()
+        .unwrap();
+    assert_eq!(no_osc.strip(s), "red\x1b]0;title\x07");
+    let pm_only = AnsiRegexBuilder::new()
+        .families([EscapeKind::Pm])
+        .build()
+        .unwrap();
+    assert_eq!(pm_only.strip("a\x1b^secret\x1b\\b\x1b[1m"), "ab\x1b[1m");
+    let none = AnsiRegexBuilder::new().families([]).build().unwrap();
+    assert!(!none.is_match(s));
+}
+
+#[test]
+fn c1_introducers() {
+    let s = "\u{9b}1mx\x1b]0;t\u{9c}y";
+    let with = AnsiRegexBuilder::new().build().unwrap();
+    let without = AnsiRegexBuilder::new().c1(false).build().unwrap();
+    assert_eq!(with.strip(s), "xy");
+    assert_eq!(without.strip(s), s);
+    assert_eq!(without.strip("\x1b[1mx\x1b]0;t\x07y"), "xy");
+}
+
+#[test]
+fn strict_csi() {
+    let re = AnsiRegexBuilder::new().strict_csi(true).build().unwrap();
+    assert_eq!(re.strip("\x1b[2 qa\x1b[5Xb"), "ab");
+}
+
+#[test]
+fn max_osc_len() {
+    let re = AnsiRegexBuilder::new().max_osc_len(8).build().unwrap();
+    assert_eq!(re.strip("\x1b]0;short\x07x"), "x");
+    let long = "\x1b]0;a much longer title\x07x";
+    assert_eq!(re.strip(long), long);
+}
+
+#[test]
+fn evicted_regex_stays_usable() {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let kept = AnsiRegexBuilder::new().max_osc_len(1).build().unwrap();
+    for len in 2..100 {
+        AnsiRegexBuilder::new().max_osc_len(len).build().unwrap();
+    }
+    assert_eq!(kept.strip("\x1b]0\x07x"), "x");
+    let again = AnsiRegexBuilder::new().max_osc_len(1).build().unwrap();
+    assert_eq!(again.as_regex().as_str(), kept.as_regex().as_str());
+}
+
+#[test]
+fn without_c1_patterns() {
+    let re = AnsiRegexBuilder::new()
+        .c1(false)
+        .strict_csi(true)
+        .family(EscapeKind::Dcs, true)
+        .build()
+        .unwrap();
+    assert!(!re.as_regex().as_str().contains("\\x9"));
+    assert_eq!(re.strip("\x1bPq\x1b\\a\x1b[2 qb\x1b]0;t\x1b\\c"), "abc");
+    let c1 = "\u{90}q\u{9c}\u{9b}1m\x1b]0;t\u{9c}";
+    assert_eq!(re.strip(c1), c1);
+}
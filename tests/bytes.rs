@@ -0,0 +1,102 @@
+//! Byte-oriented matching with raw 8-bit C1 introducers.
+
+mod common;
+
+use common::UTF8_SAMPLES;
+use regex_ansi::{
+    ansi_regex, ansi_regex_bytes, strip_ansi, strip_ansi_bytes, strip_ansi_bytes_in_place,
+};
+use std::borrow::Cow;
+
+#[test]
+fn raw_c1_csi_matches() {
+    let s = b"pre\x9b31mred\x9b0m post";
+    let found: Vec<_> = ansi_regex_bytes()
+        .find_iter(s)
+        .map(|m| m.as_bytes())
+        .collect();
+    assert_eq!(found, vec![&b"\x9b31m"[..], &b"\x9b0m"[..]]);
+    assert_eq!(strip_ansi_bytes(s), &b"prered post"[..]);
+}
+
+#[test]
+fn raw_c1_string_terminator() {
+    let s = b"\x1b]8;;https://example.com\x9clabel\x1b]8;;\x9c";
+    assert_eq!(strip_ansi_bytes(s), &b"label"[..]);
+}
+
+#[test]
+fn utf8_encoded_c1_removed_whole() {
+    let s = "a\u{9b}1mb\x1b]0;t\u{9c}c";
+    assert_eq!(strip_ansi_bytes(s.as_bytes()), &b"abc"[..]);
+}
+
+#[test]
+fn invalid_utf8_survives() {
+    let mut v = b"\xff\xfe\x1b[1mbold\x1b[0m\x80".to_vec();
+    strip_ansi_bytes_in_place(&mut v);
+    assert_eq!(v, b"\xff\xfebold\x80");
+}
+
+#[test]
+fn borrows_when_clean() {
+    assert!(matches!(strip_ansi_bytes(b"\xffplain"), Cow::Borrowed(_)));
+}
+
+#[test]
+fn agrees_with_str_regex_on_utf8() {
+    let samples = [
+        "\x1b[0;33;49;3;9;4mbar",
+        "\x1b[38:2::12:34:56mX",
+        "\x1b]8;;https://example.com\x07label\x1b]8;;\x07",
+        "\x1b(B\x1b#8\x1bc\x1b5n tail",
+        "\x1b]8;;https://example.com\u{9c}label\x1b]8;;\u{9c}",
+    ];
+    for s in samples {
+        let str_matches: Vec<_> = ansi_regex().find_iter(s).map(|m| m.range()).collect();
+        let byte_matches: Vec<_> = ansi_regex_bytes()
+            .find_iter(s.as_bytes())
+            .map(|m| m.range())
+            .collect();
+        assert_eq!(str_matches, byte_matches, "{s:?}");
+    }
+}
+
+#[test]
+fn utf8_continuation_bytes_are_not_c1() {
+    for s in UTF8_SAMPLES {
+        let expected = strip_ansi(s);
+        assert_eq!(strip_ansi_bytes(s.as_bytes()), expected.as_bytes(), "{s:?}");
+        let mut v = s.as_bytes().to_vec();
+        strip_ansi_bytes_in_place(&mut v);
+        assert_eq!(v, expected.as_bytes(), "{s:?}");
+    }
+    assert!(matches!(
+        strip_ansi_bytes("ěś些Ü".as_bytes()),
+        Cow::Borrowed(_)
+    ));
+}
+
+#[test]
+fn raw_c1_next_to_utf8() {
+    // The second 0x9B follows a complete `ě`, so it is a raw CSI.
+    assert_eq!(strip_ansi_bytes(b"\xc4\x9b\x9b1mX"), "ěX".as_bytes());
+    assert_eq!(strip_ansi_bytes(b"\x1b]0;\xc3\x9c\x9cY"), &b"Y"[..]);
+    // A lead byte without its continuation does not protect the 0x9B.
+    assert_eq!(strip_ansi_bytes(b"\xe4\x9b1mZ"), &b"\xe4Z"[..]);
+}
+
+#[test]
+fn in_place_matches_strip_ansi_bytes() {
+    for s in [
+        &b"pre\x9b31mred\x9b0m post"[..],
+        b"\x1b]8;;https://example.com\x9clabel\x1b]8;;\x9c",
+        b"\xc4\x9b\x9b1mX\xe4\x9b1mZ\x1b]0;\xc3\x9c\x9cY",
+        b"\x9b1m\x9b\x9b2m\xff\x9b",
+        "ě\u{9b}1mś\x1b]0;Ü\u{9c}些".as_bytes(),
+    ] {
+        let mut v = s.to_vec();
+        strip_ansi_bytes_in_place(&mut v);
+        assert_eq!(strip_ansi_bytes(s), v, "{:?}", String::from_utf8_lossy(s));
+    }
+}
@@ -0,0 +1,49 @@
+//! Fixtures shared by the byte and streaming test suites.
+#![allow(dead_code)]
+
+use regex_ansi::strip_ansi_bytes;
+
+/// Text whose 0x9B / 0x9C bytes belong to UTF-8 characters, not C1 controls:
+/// ě = C4 9B, ś = C5 9B, Ü = C3 9C, 些 = E4 BA 9B.
+pub const UTF8_SAMPLES: [&str; 6] = [
+    "změna śmierć 些1 \x1b]0;Über\x07x",
+    "Ü\x1b[1mě\x1b[0m\x1b]0;些\u{9c}ś",
+    "ěn śm 些m Ü\x1b[1mbold\x1b[0m",
+    "\x1b]8;;https://e.test/Über\x1b\\Über\x1b]8;;\x1b\\",
+    "\x1b]0;Ü\u{9c}tail",
+    "\u{9b}1mě\u{9b}0m",
+];
+
+/// Inputs for the streaming adapters, ending with raw 8-bit C1 bytes and
+/// invalid UTF-8.
+pub fn samples() -> Vec<Vec<u8>> {
+    let mut v: Vec<Vec<u8>> = [
+        "plain text only",
+        "\x1b[31mRed\x1b[0m and \x1b[38;5;160mIDX\x1b[0m",
+        "\x1b[0;33;49;3;9;4mbar\x1b[m",
+        "\x1b]8;;https://example.com\x07label\x1b]8;;\x07",
+        "\x1b]8;;https://example.com\x1b\\label\x1b]8;;\x1b\\",
+        "\x1b]0;title\u{9c}after",
+        "a\x1b5nb\x1b7c\x1b8d\x1b#8e\x1b(Bf\x1bmg",
+        "\x1b[5;x \x1b[12345m \x1b[4:3m",
+        "\x1b]unterminated \x1b[1mbold",
+        "\x1b\x1b[2J\x1b",
+        "\x1b\x1b[2J\x1b[12",
+        "utf8 \u{9b}31mé\u{9b}0m done\u{c2}",
+    ]
+    .iter()
+    .chain(&UTF8_SAMPLES)
+    .map(|s| s.as_bytes().to_vec())
+    .collect();
+    v.push(b"raw \x9b1mX\x9b0m \x1b]8;;u\x9cY\x1b]8;;\x9c \xff\xfe".to_vec());
+    // Raw C1 next to UTF-8 characters, complete and cut short.
+    v.push(
+        b"\xc4\x9b\x9b1mX \xe4\x9b1mZ \x1b]0;\xc3\x9c\x9cY \x1b]0;\xe4\x9c\x9b\x07\xf0".to_vec(),
+    );
+    v
+}
+
+/// What every stripper must produce for `input`.
+pub fn stripped(input: &[u8]) -> Vec<u8> {
+    strip_ansi_bytes(input).into_owned()
+}
@@ -0,0 +1,82 @@
+//! Parameter parsing for sequences matched by the CSI branch.
+
+use regex_ansi::{ansi_regex, tokenize, Csi, Token};
+
+fn parse(s: &str) -> Csi<'_> {
+    Csi::parse(s).unwrap_or_else(|| panic!("failed to parse {s:?}"))
+}
+
+#[test]
+fn truecolor_colon_form() {
+    let csi = parse("\x1b[38:2::12:34:56m");
+    assert_eq!(csi.final_byte(), 'm');
+    assert_eq!(csi.private_marker(), None);
+    assert_eq!(csi.params().len(), 1);
+    let p = &csi.params()[0];
+    assert_eq!(p.value(), Some(38));
+    assert_eq!(
+        p.subparams(),
+        &[Some(2), None, Some(12), Some(34), Some(56)]
+    );
+}
+
+#[test]
+fn semicolon_params_and_empty_fields() {
+    let csi = parse("\x1b[0;33;49;3;9;4m");
+    let values: Vec<_> = csi.params().iter().map(|p| p.value()).collect();
+    assert_eq!(
+        values,
+        vec![Some(0), Some(33), Some(49), Some(3), Some(9), Some(4)]
+    );
+    assert!(csi.params().iter().all(|p| !p.has_subparams()));
+
+    let home = parse("\x1b[;H");
+    assert_eq!(home.params().len(), 2);
+    assert_eq!(home.param(0), None);
+    assert_eq!(home.params()[1].value_or(1), 1);
+
+    assert!(parse("\x1b[m").params().is_empty());
+    assert_eq!(parse("\x1b[0m").param(0), Some(0));
+}
+
+#[test]
+fn private_marker_and_intermediates() {
+    let csi = parse("\x1b[?25l");
+    assert_eq!(csi.private_marker(), Some('?'));
+    assert_eq!(csi.param(0), Some(25));
+    assert_eq!(csi.final_byte(), 'l');
+
+    let csi = parse("\x1b[>4;2m");
+    assert_eq!(csi.private_marker(), Some('>'));
+    assert_eq!(csi.param(1), Some(2));
+
+    let csi = parse("\x1b[2 q");
+    assert_eq!(csi.intermediates(), " ");
+    assert_eq!(csi.final_byte(), 'q');
+}
+
+#[test]
+fn c1_introducer() {
+    let csi = parse("\u{9b}1;31m");
+    assert_eq!(csi.param(1), Some(31));
+}
+
+#[test]
+fn colon_fixtures_parse_from_tokens() {
+    let samples = [
+        "\x1b[4:3m",
+        "\x1b[38:5:123m",
+        "\x1b[58:2::255:0:0m",
+        "\x1b[48:6::0:0:0:64m",
+        "\x1b[38:2:68:68:68:48:2:0:0:0m",
+    ];
+    for s in samples {
+        let m = ansi_regex().find(s).unwrap().as_str();
+        let Some(Token::Escape(e)) = tokenize(s).next() else {
+            panic!("no escape in {s:?}");
+        };
+        let csi = e.csi().expect("CSI");
+        assert_eq!(csi.as_str(), m);
+        assert_eq!(csi.final_byte(), 'm');
+    }
+}
@@ -1,6 +1,10 @@
 //! Exhaustive parity tests ported from chalk/ansi-regex test.js + ansi-codes fixtures.
 
-use regex_ansi::ansi_regex;
+use regex_ansi::{
+    ansi_regex, strip_ansi, strip_ansi_bytes_in_place, strip_ansi_cow, strip_ansi_in_place,
+    visualize,
+};
+use std::borrow::Cow;
 
 // Consumption characters from upstream test (kept identical ordering)
 const CONSUMPTION_CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ!@#$%^&*()_+1234567890-=[]{};'\":./>?,<\\|";
@@ -235,7 +239,11 @@ fn upstream_sample_sequences_match() {
         // ESC m (implicit SGR reset) now explicitly supported in pattern via short escape branch
         "foo\x1bmfoo",
     ] {
-        assert!(re.is_match(sample), "expected match for {sample:?}");
+        assert!(
+            re.is_match(sample),
+            "expected match for {}",
+            visualize(sample)
+        );
     }
 }
 
@@ -348,7 +356,7 @@ fn negative_no_esc_bracketed() {
     ];
     let re = ansi_regex();
     for s in samples {
-        assert!(re.find(s).is_none(), "false positive {s}");
+        assert!(re.find(s).is_none(), "false positive in {}", visualize(s));
     }
 }
 
@@ -382,6 +390,53 @@ fn exhaustive_code_sets_basic() {
     }
 }
 
+#[test]
+fn strip_helpers_agree_on_code_sets() {
+    let sets = [
+        vt52_codes(),
+        ansi_compatible_codes(),
+        common_codes(),
+        other_codes(),
+        urxvt_codes(),
+    ];
+    for &(code, _) in sets.iter().flat_map(|s| s.iter()) {
+        check_strip(code);
+    }
+}
+
+#[test]
+fn strip_borrows_plain_text() {
+    for s in ["", "hello", CONSUMPTION_CHARS, "[31m not an escape"] {
+        assert!(matches!(strip_ansi(s), Cow::Borrowed(b) if b == s));
+        assert!(matches!(strip_ansi_cow(Cow::Borrowed(s)), Cow::Borrowed(_)));
+    }
+}
+
+#[test]
+fn strip_in_place_keeps_multibyte_text() {
+    let mut s = String::from("\x1b[1m日本\x1b[0m語 \x1b]8;;https://example.com\x07é\x1b]8;;\x07");
+    let cap = s.capacity();
+    strip_ansi_in_place(&mut s);
+    assert_eq!(s, "日本語 é");
+    assert_eq!(s.capacity(), cap);
+}
+
+fn check_strip(code: &str) {
+    let wrapped = format!("hel\x1b{}lo", code);
+    let expected = ansi_regex().replace_all(&wrapped, "").into_owned();
+    let shown = visualize(&wrapped);
+    assert_eq!(strip_ansi(&wrapped), expected, "strip_ansi {shown}");
+    let owned = strip_ansi_cow(Cow::Owned(wrapped.clone()));
+    assert!(matches!(owned, Cow::Owned(_)));
+    assert_eq!(owned, expected, "strip_ansi_cow {shown}");
+    let mut s = wrapped.clone();
+    strip_ansi_in_place(&mut s);
+    assert_eq!(s, expected, "strip_ansi_in_place {shown}");
+    let mut v = wrapped.into_bytes();
+    strip_ansi_bytes_in_place(&mut v);
+    assert_eq!(v, expected.as_bytes(), "strip_ansi_bytes_in_place {shown}");
+}
+
 fn check_code(re: &regex::Regex, code: &str) {
     // Skip if ends in digit (upstream test logic)
     if code
@@ -394,14 +449,18 @@ fn check_code(re: &regex::Regex, code: &str) {
     }
     let esc = format!("\x1b{}", code);
     let wrapped = format!("hel{}lo", esc);
-    assert!(re.is_match(&wrapped), "did not match {code}");
+    assert!(
+        re.is_match(&wrapped),
+        "did not match {}",
+        visualize(&wrapped)
+    );
     let first = re.find(&wrapped).unwrap().as_str();
-    assert_eq!(first, esc, "overconsumed {code}");
+    assert_eq!(first, esc, "overconsumed {}", visualize(&wrapped));
     assert_eq!(re.replace(&wrapped, ""), "hello");
     for ch in CONSUMPTION_CHARS.chars() {
         let sample = format!("{}{}", esc, ch);
         let m = re.find(&sample).unwrap().as_str();
-        assert_eq!(m, esc, "overconsume {code} before {ch}");
+        assert_eq!(m, esc, "overconsume {}", visualize(&sample));
         assert_eq!(re.replace(&sample, ""), ch.to_string());
     }
 }
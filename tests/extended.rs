@@ -0,0 +1,67 @@
+//! Opt-in DCS / SOS / PM / APC support in the extended pattern.
+
+use regex_ansi::{ansi_regex, ansi_regex_extended, AnsiTokenizer, EscapeKind, Token};
+
+const SIXEL: &str = "\x1bPq#0;2;0;0;0#1;2;100;100;0#1~~@@vv@@~~@@~~$-\x1b\\";
+const KITTY: &str = "\x1b_Gf=100,a=T;iVBORw0KGgo=\x1b\\";
+const TMUX: &str = "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\";
+
+#[test]
+fn strips_control_strings() {
+    for (seq, kind) in [
+        (SIXEL, EscapeKind::Dcs),
+        (KITTY, EscapeKind::Apc),
+        (TMUX, EscapeKind::Dcs),
+        ("\x1bXstart of string\x1b\\", EscapeKind::Sos),
+        ("\x1b^private\x1b\\", EscapeKind::Pm),
+        ("\u{90}1$r0m\u{9c}", EscapeKind::Dcs),
+        ("\u{98}s\u{9c}", EscapeKind::Sos),
+        ("\u{9e}p\u{9c}", EscapeKind::Pm),
+        ("\u{9f}Ga=d\u{9c}", EscapeKind::Apc),
+    ] {
+        let text = format!("before{seq}after");
+        assert_eq!(ansi_regex_extended().replace_all(&text, ""), "beforeafter");
+        let tokens: Vec<Token<'_>> =
+            AnsiTokenizer::with_regex(&text, ansi_regex_extended()).collect();
+        assert_eq!(tokens.len(), 3, "{seq:?}");
+        match tokens[1] {
+            Token::Escape(e) => {
+                assert_eq!(e.as_str(), seq);
+                assert_eq!(e.kind(), kind, "{seq:?}");
+            }
+            other => panic!("expected escape, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn upstream_pattern_is_unchanged() {
+    // Without opting in the payload stays in the text.
+    let stripped = ansi_regex().replace_all(KITTY, "");
+    assert!(stripped.contains("iVBORw0KGgo="));
+    assert_ne!(ansi_regex().replace_all(SIXEL, ""), "");
+}
+
+#[test]
+fn superset_of_upstream_matches() {
+    let s = "\x1b[31mred\x1b[0m \x1b]8;;https://x.y\x07link\x1b]8;;\x07 \x1b(B\x1b#8\x1b7\x1b5n \u{9b}1m";
+    let upstream: Vec<&str> = ansi_regex().find_iter(s).map(|m| m.as_str()).collect();
+    let extended: Vec<&str> = ansi_regex_extended()
+        .find_iter(s)
+        .map(|m| m.as_str())
+        .collect();
+    assert_eq!(upstream, extended);
+}
+
+#[test]
+fn only_string_terminator_ends_control_strings() {
+    // BEL does not end a DCS, and unterminated strings are left alone.
+    for s in ["\x1bPq data\x07 more", "\x1b_Gpartial", "\x1bPq\x1b[1m"] {
+        let m = ansi_regex_extended().find(s).map(|m| m.as_str());
+        assert!(m.is_none() || m == Some("\x1b[1m"), "{s:?} matched {m:?}");
+    }
+    assert_eq!(
+        ansi_regex_extended().replace_all("\x1bPq data\x07 more\x1b\\!", ""),
+        "!"
+    );
+}
@@ -0,0 +1,105 @@
+//! Conversion of styled terminal output to HTML.
+
+use regex_ansi::{to_html, HtmlOptions, XTERM_PALETTE};
+
+#[test]
+fn inline_styles_and_colors() {
+    for (input, expected) in [
+        ("plain", "plain"),
+        (
+            "\x1b[31mred\x1b[39m",
+            "<span style=\"color:#cd0000\">red</span>",
+        ),
+        (
+            "\x1b[92;44mx",
+            "<span style=\"color:#00ff00;background-color:#0000ee\">x</span>",
+        ),
+        ("\x1b[38;5;196mx", "<span style=\"color:#ff0000\">x</span>"),
+        ("\x1b[38;5;244mx", "<span style=\"color:#808080\">x</span>"),
+        ("\x1b[38;5;9mx", "<span style=\"color:#ff0000\">x</span>"),
+        (
+            "\x1b[48;2;1;2;3mx",
+            "<span style=\"background-color:#010203\">x</span>",
+        ),
+        (
+            "\x1b[3;4:3;9;58;5;1mx",
+            "<span style=\"font-style:italic;text-decoration-line:underline line-through;\
+             text-decoration-style:wavy;text-decoration-color:#cd0000\">x</span>",
+        ),
+        (
+            "\x1b[7mx",
+            "<span style=\"color:#000000;background-color:#e5e5e5\">x</span>",
+        ),
+    ] {
+        assert_eq!(to_html(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn spans_merge_and_close() {
+    assert_eq!(
+        to_html("\x1b[1ma\x1b[Kb\x1b[22mc\x1b[1md"),
+        "<span style=\"font-weight:bold\">ab</span>c<span style=\"font-weight:bold\">d</span>"
+    );
+}
+
+#[test]
+fn blink_only_with_classes() {
+    let input = "a\x1b[5mb\x1b[1mc\x1b[0md";
+    assert_eq!(
+        to_html(input),
+        "ab<span style=\"font-weight:bold\">c</span>d"
+    );
+    assert_eq!(
+        HtmlOptions::new().css_classes(true).to_html(input),
+        "a<span class=\"ansi-blink\">b</span><span class=\"ansi-bold ansi-blink\">c</span>d"
+    );
+}
+
+#[test]
+fn classes_and_stylesheet() {
+    let opts = HtmlOptions::new().css_classes(true).class_prefix("t-");
+    assert_eq!(
+        opts.to_html("\x1b[1;4:2;33;100mx\x1b[0m\x1b[38;2;9;9;9my"),
+        "<span class=\"t-bold t-underline t-underline-double t-fg-3 t-bg-8\">x</span>\
+         <span style=\"color:#090909\">y</span>"
+    );
+    let css = opts.stylesheet();
+    assert!(css.contains(".t-fg-3{color:#cdcd00}"));
+    assert!(css.contains(".t-underline.t-strike{text-decoration-line:underline line-through}"));
+}
+
+#[test]
+fn custom_palette() {
+    let mut palette = XTERM_PALETTE;
+    palette[1] = (0xaa, 0x11, 0x22);
+    let opts = HtmlOptions::new().palette(palette);
+    assert_eq!(
+        opts.to_html("\x1b[31mx"),
+        "<span style=\"color:#aa1122\">x</span>"
+    );
+    assert!(opts
+        .css_classes(true)
+        .stylesheet()
+        .contains(".ansi-fg-1{color:#aa1122}"));
+}
+
+#[test]
+fn links_and_escaping() {
+    let s = "<\x1b]8;;https://e.test/?a=1&b=\"2\"\x1b\\\x1b[1mgo\x1b[0m & see\x1b]8;;\x1b\\>";
+    assert_eq!(
+        to_html(s),
+        "&lt;<a href=\"https://e.test/?a=1&amp;b=&quot;2&quot;\">\
+         <span style=\"font-weight:bold\">go</span> &amp; see</a>&gt;"
+    );
+    let js = "\x1b]8;;javascript:alert(1)\x07x\x1b]8;;\x07";
+    assert_eq!(to_html(js), "x");
+    assert_eq!(
+        HtmlOptions::new()
+            .link_schemes(["javascript"])
+            .hyperlinks(false)
+            .to_html(js),
+        "x"
+    );
+    assert_eq!(to_html("a\x1bb\x07c\td\n"), "abc\td\n");
+}
@@ -0,0 +1,167 @@
+//! OSC 8 hyperlink construction, extraction and rewriting.
+
+use regex_ansi::{
+    ansi_regex, hyperlink, hyperlinks, map_hyperlinks, remove_hyperlinks, HyperlinkBuilder,
+    Terminator,
+};
+use std::borrow::Cow;
+
+#[test]
+fn pairs_each_terminator() {
+    for (term, kind) in [
+        ("\x07", Terminator::Bel),
+        ("\x1b\\", Terminator::St),
+        ("\u{9c}", Terminator::C1St),
+    ] {
+        let s = format!("a\x1b]8;id=x:k=v;https://example.com{term}label\x1b]8;;{term}b");
+        let links: Vec<_> = hyperlinks(&s).collect();
+        assert_eq!(links.len(), 1);
+        let link = &links[0];
+        assert_eq!(link.url(), "https://example.com");
+        assert_eq!(link.params(), "id=x:k=v");
+        assert_eq!(
+            (link.id(), link.param("k"), link.param("z")),
+            (Some("x"), Some("v"), None)
+        );
+        assert_eq!(link.label(), "label");
+        assert_eq!(&s[link.label_range()], "label");
+        assert_eq!(link.range(), 1..s.len() - 1);
+        assert_eq!(link.terminator(), kind);
+        assert_eq!(kind.as_str(), term);
+    }
+}
+
+#[test]
+fn implicit_and_missing_closes() {
+    let s = "\x1b]8;;https://a\x07A\x1b]8;;https://b\x07B\x1b]8;;\x07\x1b]8;;\x07 \x1b]8;;https://c\x07C";
+    let links: Vec<_> = hyperlinks(s).map(|l| (l.url(), l.label())).collect();
+    assert_eq!(
+        links,
+        [("https://a", "A"), ("https://b", "B"), ("https://c", "C")]
+    );
+    let first = hyperlinks(s).next().unwrap();
+    assert_eq!(&s[first.range()], "\x1b]8;;https://a\x07A");
+}
+
+#[test]
+fn label_keeps_styles_and_other_osc_are_ignored() {
+    let s = "\x1b]0;title\x07\x1b]8;;https://x\x1b\\\x1b[1mbold\x1b[0m\x1b]8;;\x1b\\";
+    let link = hyperlinks(s).next().unwrap();
+    assert_eq!(link.label(), "\x1b[1mbold\x1b[0m");
+    assert_eq!(hyperlinks("plain \x1b[31mtext").count(), 0);
+}
+
+#[test]
+fn rewrite_and_remove() {
+    let s = "go \x1b]8;id=1;http://a\x1b\\A\x1b]8;;\x1b\\ and \x1b]8;;mailto:b\x07B\x1b]8;;\x07.";
+    let out = map_hyperlinks(s, |link| Some(link.url().replace("http:", "https:")));
+    assert_eq!(
+        out,
+        "go \x1b]8;id=1;https://a\x1b\\A\x1b]8;;\x1b\\ and \x1b]8;;mailto:b\x07B\x1b]8;;\x07."
+    );
+    let out = map_hyperlinks(s, |link| {
+        link.url()
+            .starts_with("http")
+            .then(|| link.url().to_owned())
+    });
+    assert_eq!(out, "go \x1b]8;id=1;http://a\x1b\\A\x1b]8;;\x1b\\ and B.");
+    assert_eq!(remove_hyperlinks(s), "go A and B.");
+}
+
+#[test]
+fn rewritten_urls_are_encoded() {
+    let s = "\x1b]8;;http://a\x07A\x1b]8;;\x07";
+    let out = map_hyperlinks(s, |_| Some("http://x\x07\x1b]0;pwn\u{9c}/ä b".to_owned()));
+    assert_eq!(
+        out,
+        "\x1b]8;;http://x%07%1B]0;pwn%C2%9C/%C3%A4%20b\x07A\x1b]8;;\x07"
+    );
+    let links: Vec<_> = hyperlinks(&out).collect();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].label(), "A");
+}
+
+#[test]
+fn unchanged_input_is_borrowed() {
+    let s = "x \x1b]8;;https://a\x07A\x1b]8;;\x07";
+    assert!(matches!(
+        map_hyperlinks(s, |l| Some(l.url().to_owned())),
+        Cow::Borrowed(_)
+    ));
+    assert!(matches!(
+        remove_hyperlinks("no links \x1b[1m"),
+        Cow::Borrowed(_)
+    ));
+}
+
+#[test]
+fn builder_params_and_terminators() {
+    let b = HyperlinkBuilder::new("https://x.test/?q=1;2")
+        .param("k", "v")
+        .id("one")
+        .id("two");
+    assert_eq!(b.open(), "\x1b]8;k=v:id=two;https://x.test/?q=1;2\x1b\\");
+    assert_eq!(b.close(), "\x1b]8;;\x1b\\");
+    let b = b.terminator(Terminator::C1St);
+    assert_eq!(
+        b.build("L"),
+        "\x1b]8;k=v:id=two;https://x.test/?q=1;2\u{9c}L\x1b]8;;\u{9c}"
+    );
+}
+
+#[test]
+fn encodes_sequence_breaking_characters() {
+    let url = "https://x.test/\x07\x1b\\\u{9c} é%41";
+    let b = HyperlinkBuilder::new(url).id("a:b;c=d\x07");
+    assert_eq!(
+        b.open(),
+        "\x1b]8;id=a%3Ab%3Bc%3Dd%07;https://x.test/%07%1B\\%C2%9C%20%C3%A9%41\x1b\\"
+    );
+}
+
+#[test]
+fn output_round_trips() {
+    let hostile = [
+        "https://e.test/\x07x",
+        "https://e.test/\x1b\\y",
+        "https://e.test/\u{9c}z",
+        "https://e.test/ok",
+    ];
+    for url in hostile {
+        for term in [Terminator::Bel, Terminator::St, Terminator::C1St] {
+            let s = format!(
+                "<{}>",
+                HyperlinkBuilder::new(url)
+                    .id("i")
+                    .terminator(term)
+                    .build("label")
+            );
+            let seqs: Vec<_> = ansi_regex().find_iter(&s).map(|m| m.as_str()).collect();
+            assert_eq!(seqs.len(), 2, "{s:?}");
+            assert_eq!(ansi_regex().replace_all(&s, ""), "<label>");
+            let links: Vec<_> = hyperlinks(&s).collect();
+            assert_eq!(links.len(), 1);
+            assert_eq!(
+                (links[0].id(), links[0].label(), links[0].terminator()),
+                (Some("i"), "label", term)
+            );
+            assert!(links[0].url().starts_with("https://e.test/"));
+            assert_eq!(links[0].range(), 1..s.len() - 1);
+        }
+    }
+    assert_eq!(remove_hyperlinks(&hyperlink("https://e.test", "x")), "x");
+}
+
+#[test]
+fn malformed_openers_are_ignored() {
+    for s in [
+        "\x1b]8;\x07x",
+        "\x1b]8;\x1b\\x",
+        "\x1b]8;x\u{9c}x",
+        "\x1b]8;",
+        "\x1b]8;;",
+    ] {
+        assert_eq!(hyperlinks(s).count(), 0, "{s:?}");
+        assert_eq!(remove_hyperlinks(s), s, "{s:?}");
+    }
+}
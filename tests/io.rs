@@ -0,0 +1,175 @@
+//! Streaming strippers must agree with the regex regardless of chunking.
+
+mod common;
+
+use common::{samples, stripped};
+use regex_ansi::{StripReader, StripWriter};
+use std::io::{BufRead, Read, Write};
+
+fn write_chunked(input: &[u8], chunk: usize) -> Vec<u8> {
+    let mut w = StripWriter::new(Vec::new());
+    for part in input.chunks(chunk) {
+        w.write_all(part).unwrap();
+    }
+    w.finish().unwrap()
+}
+
+#[test]
+fn writer_matches_regex_for_every_chunk_size() {
+    for input in samples() {
+        let expected = stripped(&input);
+        for chunk in 1..=input.len().max(1) {
+            assert_eq!(
+                write_chunked(&input, chunk),
+                expected,
+                "{:?} in chunks of {chunk}",
+                String::from_utf8_lossy(&input)
+            );
+        }
+    }
+}
+
+#[test]
+fn writer_holds_only_incomplete_tail() {
+    let mut w = StripWriter::new(Vec::new());
+    w.write_all(b"abc\x1b[38;5").unwrap();
+    assert_eq!(w.get_ref(), b"abc");
+    w.write_all(b";1mdef").unwrap();
+    assert_eq!(w.get_ref(), b"abcdef");
+    w.write_all(b"\x1b(").unwrap();
+    w.flush().unwrap();
+    assert_eq!(w.get_ref(), b"abcdef");
+    w.write_all(b"Z").unwrap();
+    assert_eq!(w.get_ref(), b"abcdef\x1b(Z");
+}
+
+#[test]
+fn writer_gives_up_on_oversized_sequence() {
+    let mut w = StripWriter::with_max_pending(Vec::new(), 8);
+    w.write_all(b"\x1b]0;a very long title").unwrap();
+    assert_eq!(w.get_ref(), b"\x1b]0;a very long title");
+    w.write_all(b"\x07x").unwrap();
+    assert_eq!(w.finish().unwrap(), b"\x1b]0;a very long title\x07x");
+}
+
+// Writer whose every other call fails, accepting at most `step` bytes
+// otherwise.
+#[derive(Default)]
+struct Flaky {
+    data: Vec<u8>,
+    step: usize,
+    fail: bool,
+}
+
+impl Write for Flaky {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.fail = !self.fail;
+        if self.fail {
+            return Err(std::io::Error::other("try again"));
+        }
+        let n = self.step.min(buf.len());
+        self.data.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn writer_retries_after_inner_errors() {
+    let input = "a\x1b[1mbc\x1b]0;t\x07def\x1b[0mghi ě\x1b[38;5;1mjkl\n".as_bytes();
+    for chunk in 1..=input.len() {
+        let mut w = StripWriter::new(Flaky {
+            step: 2,
+            ..Flaky::default()
+        });
+        for mut part in input.chunks(chunk) {
+            // Like `write_all`, but retrying on every error.
+            while !part.is_empty() {
+                if let Ok(n) = w.write(part) {
+                    part = &part[n..];
+                }
+            }
+        }
+        while w.flush().is_err() {}
+        let inner = w.finish().unwrap();
+        assert_eq!(inner.data, stripped(input), "chunks of {chunk}");
+    }
+}
+
+// Reader that hands out at most `step` bytes per call.
+struct Trickle<'a> {
+    data: &'a [u8],
+    step: usize,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.step.min(buf.len()).min(self.data.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn reader_matches_regex_for_every_chunk_size() {
+    for input in samples() {
+        let expected = stripped(&input);
+        for step in 1..=input.len().max(1) {
+            let mut out = Vec::new();
+            StripReader::new(Trickle { data: &input, step })
+                .read_to_end(&mut out)
+                .unwrap();
+            assert_eq!(
+                out,
+                expected,
+                "{:?} in reads of {step}",
+                String::from_utf8_lossy(&input)
+            );
+        }
+    }
+}
+
+#[test]
+fn reader_buf_read_lines() {
+    let data = b"\x1b[32mok\x1b[0m first\n\x1b]0;t\x07second\n";
+    let lines: Vec<String> = StripReader::new(Trickle { data, step: 3 })
+        .lines()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(lines, vec!["ok first", "second"]);
+}
+
+#[test]
+fn reader_unterminated_osc_is_bounded() {
+    let mut data = b"\x1b]8;;".to_vec();
+    data.extend([b'x'; 100]);
+    data.extend(b"\x1b[1mend");
+    let mut out = Vec::new();
+    StripReader::with_max_pending(
+        Trickle {
+            data: &data,
+            step: 7,
+        },
+        32,
+    )
+    .read_to_end(&mut out)
+    .unwrap();
+    assert!(out.starts_with(b"\x1b]8;;xxx"));
+    assert!(out.ends_with(b"xend"));
+}
+
+#[test]
+fn reader_keeps_utf8_split_across_reads() {
+    let data = "změna \x1b[1mśmierć\x1b[0m\n些1 \x1b]0;Über\x07Ü\n".as_bytes();
+    for step in 1..=4 {
+        let lines: Vec<String> = StripReader::new(Trickle { data, step })
+            .lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, vec!["změna śmierć", "些1 Ü"], "reads of {step}");
+    }
+}
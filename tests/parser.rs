@@ -0,0 +1,248 @@
+//! The push parser must split and classify input exactly like the regex.
+
+mod common;
+
+use common::UTF8_SAMPLES;
+use regex_ansi::{
+    ansi_regex_bytes, strip_ansi_bytes, AnsiTokenizer, EscapeKind, Event, Parser, Token,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Part {
+    Text(Vec<u8>),
+    Escape(Option<EscapeKind>, Vec<u8>),
+}
+
+fn push_text(parts: &mut Vec<Part>, text: &[u8]) {
+    if let Some(Part::Text(prev)) = parts.last_mut() {
+        prev.extend_from_slice(text);
+    } else if !text.is_empty() {
+        parts.push(Part::Text(text.to_vec()));
+    }
+}
+
+// Kinds are only compared where the tokenizer can classify the match as `&str`.
+fn kind_of(seq: &[u8]) -> Option<EscapeKind> {
+    std::str::from_utf8(seq).ok().map(EscapeKind::of)
+}
+
+fn regex_parts(input: &[u8]) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for m in ansi_regex_bytes().find_iter(input) {
+        push_text(&mut parts, &input[last..m.start()]);
+        parts.push(Part::Escape(kind_of(m.as_bytes()), m.as_bytes().to_vec()));
+        last = m.end();
+    }
+    push_text(&mut parts, &input[last..]);
+    parts
+}
+
+// The split the parser must reproduce where a regex gives it exactly: the
+// tokenizer's on UTF-8, and the byte regex's when no byte could lead a UTF-8
+// character that a 0x9B / 0x9C continues.
+fn expected_parts(input: &[u8]) -> Option<Vec<Part>> {
+    if let Ok(s) = std::str::from_utf8(input) {
+        let mut parts = Vec::new();
+        for token in AnsiTokenizer::new(s) {
+            match token {
+                Token::Text(text) => push_text(&mut parts, text.as_bytes()),
+                Token::Escape(seq) => parts.push(Part::Escape(
+                    Some(seq.kind()),
+                    seq.as_str().as_bytes().to_vec(),
+                )),
+            }
+        }
+        return Some(parts);
+    }
+    let has_lead = input.iter().any(|b| matches!(b, 0xC3..=0xF4));
+    (!has_lead).then(|| regex_parts(input))
+}
+
+fn parser_parts(input: &[u8], chunk: usize) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut parser = Parser::new();
+    let mut on_event = |e: Event<'_>| match e {
+        Event::Text(text) => {
+            assert!(!text.is_empty());
+            push_text(&mut parts, text);
+        }
+        Event::Escape(kind, seq) => {
+            let kind = kind_of(seq).map(|k| {
+                assert_eq!(k, kind, "{seq:?}");
+                kind
+            });
+            parts.push(Part::Escape(kind, seq.to_vec()));
+        }
+    };
+    for part in input.chunks(chunk.max(1)) {
+        parser.feed(part, &mut on_event);
+    }
+    parser.finish(&mut on_event);
+    assert!(parser.pending().is_empty());
+    parts
+}
+
+fn check(input: &[u8]) {
+    let expected = expected_parts(input);
+    let stripped = strip_ansi_bytes(input);
+    for chunk in [1, 2, 3, 7, input.len()] {
+        let parts = parser_parts(input, chunk);
+        let text: Vec<u8> = parts
+            .iter()
+            .flat_map(|p| match p {
+                Part::Text(t) => t.as_slice(),
+                Part::Escape(..) => &[],
+            })
+            .copied()
+            .collect();
+        let context = format!("{:?} in chunks of {chunk}", String::from_utf8_lossy(input));
+        assert_eq!(text, *stripped, "{context}");
+        if let Some(expected) = &expected {
+            assert_eq!(&parts, expected, "{context}");
+        }
+    }
+}
+
+#[test]
+fn samples_match_regex() {
+    for s in [
+        "plain text only",
+        "\x1b[31mRed\x1b[0m and \x1b[38;5;160mIDX\x1b[0m",
+        "\x1b[0;33;49;3;9;4mbar\x1b[m",
+        "\x1b[?25l\x1b[?1049h\x1b[176;176H\x1b[2J\x1b[4:3m",
+        "\x1b]8;;https://example.com\x07label\x1b]8;;\x07",
+        "\x1b]8;;https://example.com\x1b\\label\x1b]8;;\x1b\\",
+        "\x1b]0;title\u{9c}after",
+        "a\x1b5nb\x1b7c\x1b8d\x1b#8e\x1b(Bf\x1bmg\x1b12n",
+        "\x1b[5;x \x1b[12345m \x1b[1;2;3 \x1b[;;;m",
+        "\x1b]unterminated \x1b[1mbold",
+        "\x1b\x1b[2J\x1b",
+        "utf8 \u{9b}31mé\u{9b}0m done\u{c2}",
+        "\x1b[",
+        "\x1b]8;;",
+        "\x1b[12",
+        "\x1b#",
+        "\x1b(",
+        "\x1b55",
+    ] {
+        check(s.as_bytes());
+    }
+    check(b"raw \x9b1mX\x9b0m \x1b]8;;u\x9cY\x1b]8;;\x9c \xff\xfe");
+    check(b"\xc2\x9b\xc2\x9c\xc2\xc2\x9b5;m\x1b]x\xc2\x9c");
+}
+
+#[test]
+fn utf8_continuation_bytes_are_text() {
+    for s in UTF8_SAMPLES {
+        check(s.as_bytes());
+    }
+    // Raw C1 next to complete and incomplete characters.
+    check(b"\xc4\x9b\x9b1mX \xe4\x9b1mZ \x1b]0;\xc3\x9c\x9cY \x1b]0;\xe4\x9c\x9b\x07\xf0");
+    check(b"\x1b]0;\xf0\x9f\x9c\x80\x07\x1b]0;\xe4\x9c\x1b\\");
+
+    let mut parser = Parser::new();
+    let mut parts = Vec::new();
+    parser.feed("změna".as_bytes(), |e| match e {
+        Event::Text(t) => push_text(&mut parts, t),
+        Event::Escape(..) => panic!("{e:?}"),
+    });
+    assert_eq!(parts, [Part::Text("změna".as_bytes().to_vec())]);
+}
+
+#[test]
+fn every_escape_pair_and_csi_final() {
+    for b in 0..=255u8 {
+        for tail in [&b""[..], b"x", b"1", b"n", b"\x1b"] {
+            let mut input = vec![0x1b, b];
+            input.extend_from_slice(tail);
+            check(&input);
+            let mut input = b"\x1b[1;2".to_vec();
+            input.push(b);
+            input.extend_from_slice(tail);
+            check(&input);
+            let mut input = b"\x1b[?".to_vec();
+            input.push(b);
+            input.extend_from_slice(tail);
+            check(&input);
+        }
+    }
+}
+
+#[test]
+fn random_inputs_match_regex() {
+    let alphabet: &[u8] =
+        b"\x1b\x1b\x1b[[]]();;:?#0123456789789mnABc\\\x07\x9b\x9c\xc2\xc4\xe4\xba x";
+    let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    for _ in 0..5_000 {
+        let len = (next() % 24) as usize;
+        let input: Vec<u8> = (0..len)
+            .map(|_| alphabet[next() as usize % alphabet.len()])
+            .collect();
+        check(&input);
+    }
+}
+
+#[test]
+fn emits_sequences_as_soon_as_complete() {
+    let mut parser = Parser::new();
+    let mut seen = Vec::new();
+    parser.feed(b"ab\x1b[3", |e| seen.push(format!("{e:?}")));
+    assert_eq!(seen.len(), 1);
+    assert_eq!(parser.pending(), b"\x1b[3");
+    parser.feed(b"1m", |e| seen.push(format!("{e:?}")));
+    assert_eq!(seen.len(), 2);
+    assert!(parser.pending().is_empty());
+
+    let mut osc = Vec::new();
+    parser.feed(b"\x1b]0;title\x07", |e| {
+        if let Event::Escape(kind, seq) = e {
+            osc.push((kind, seq.to_vec()));
+        }
+    });
+    assert_eq!(osc, [(EscapeKind::Osc, b"\x1b]0;title\x07".to_vec())]);
+}
+
+#[test]
+fn many_csi_parameter_groups() {
+    // Every `;` group used to add a level of recursion.
+    let mut input = b"\x1b[".to_vec();
+    for _ in 0..50_000 {
+        input.extend_from_slice(b"1;");
+    }
+    input.push(b'x');
+    let mut parser = Parser::with_max_pending(usize::MAX);
+    let mut parts = Vec::new();
+    parser.feed(&input, |e| match e {
+        Event::Text(t) => push_text(&mut parts, t),
+        Event::Escape(kind, seq) => parts.push(Part::Escape(Some(kind), seq.to_vec())),
+    });
+    parser.finish(|e| panic!("nothing left to finish: {e:?}"));
+    assert_eq!(parts, regex_parts(&input));
+}
+
+#[test]
+fn unfinished_sequence_is_bounded() {
+    let mut input = b"\x1b]0;".to_vec();
+    input.extend([b'a'; 1000]);
+    input.extend_from_slice(b"\x1b[1m");
+    let mut parser = Parser::with_max_pending(16);
+    let mut text = Vec::new();
+    let mut escapes = Vec::new();
+    for chunk in input.chunks(7) {
+        parser.feed(chunk, |e| match e {
+            Event::Text(t) => text.extend_from_slice(t),
+            Event::Escape(_, seq) => escapes.push(seq.to_vec()),
+        });
+        assert!(parser.pending().len() <= 16);
+    }
+    parser.finish(|_| unreachable!());
+    assert_eq!(text, &input[..input.len() - 4]);
+    assert_eq!(escapes, [b"\x1b[1m".to_vec()]);
+}
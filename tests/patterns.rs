@@ -0,0 +1,71 @@
+//! Branch constants and compile-time composition.
+
+use regex::Regex;
+use regex_ansi::{
+    ansi_pattern, ANSI_REGEX_PATTERN, CHARSET_PATTERN, CSI_7BIT_PATTERN, CSI_8BIT_PATTERN,
+    DEC_HASH_PATTERN, DSR_PATTERN, OSC_PATTERN, VT52_PATTERN,
+};
+
+// The published pattern must stay byte-for-byte what it was before it was
+// split into branches.
+const ORIGINAL: &str = concat!(
+    "(?:\\x1B\\][^\\x07\\x1B\\x9C]*?(?:\\x07|\\x1B\\\\|\\x9C))",
+    "|",
+    "(?:\\x1B\\[[\\[\\]()#;?]*(?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)?[0-9A-PR-TZcf-nq-uy=><~])",
+    "|",
+    "(?:\\x9B[\\[\\]()#;?]*(?:[0-9]{1,4}(?:[;:][0-9]{0,4})*)?[0-9A-PR-TZcf-nq-uy=><~])",
+    "|",
+    "(?:\\x1B[ABCDHIKJSTZ=><sum78EMcNO])",
+    "|",
+    "(?:\\x1B[()][AB012])",
+    "|",
+    "(?:\\x1B#[34568])",
+    "|",
+    "(?:\\x1B[0-9]+n)"
+);
+
+#[test]
+fn published_pattern_unchanged() {
+    assert_eq!(ANSI_REGEX_PATTERN, ORIGINAL);
+    let joined = [
+        OSC_PATTERN,
+        CSI_7BIT_PATTERN,
+        CSI_8BIT_PATTERN,
+        VT52_PATTERN,
+        CHARSET_PATTERN,
+        DEC_HASH_PATTERN,
+        DSR_PATTERN,
+    ]
+    .join("|");
+    assert_eq!(joined, ANSI_REGEX_PATTERN);
+}
+
+// A lint tool style subset, built entirely at compile time.
+const SGR_AND_LINKS: &str = ansi_pattern!(osc, csi_7bit);
+
+#[test]
+fn custom_subset() {
+    assert_eq!(SGR_AND_LINKS, format!("{OSC_PATTERN}|{CSI_7BIT_PATTERN}"));
+    let re = Regex::new(SGR_AND_LINKS).unwrap();
+    assert_eq!(
+        re.replace_all("\x1b]8;;u\x07a\x1b]8;;\x07\x1b[1mb\x1b(B\x1b7", ""),
+        "ab\x1b(B\x1b7"
+    );
+}
+
+#[test]
+fn every_branch_compiles() {
+    for p in [
+        ansi_pattern!(osc),
+        ansi_pattern!(csi_7bit),
+        ansi_pattern!(csi_8bit),
+        ansi_pattern!(vt52),
+        ansi_pattern!(charset),
+        ansi_pattern!(dec_hash),
+        ansi_pattern!(dsr),
+        ansi_pattern!(control_string),
+        ansi_pattern!(csi_strict),
+    ] {
+        Regex::new(p).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+//! Allow-list sanitizing of untrusted terminal output.
+
+use regex_ansi::{sanitize, EscapeKind, Sanitizer};
+use std::borrow::Cow;
+
+#[test]
+fn keeps_sgr_and_web_links() {
+    let s = "\x1b[1;31mred\x1b[0m \x1b]8;id=1;https://example.com\x1b\\link\x1b]8;;\x1b\\ \x1bm";
+    assert_eq!(sanitize(s), s);
+    assert!(matches!(sanitize("plain\ttext\r\n"), Cow::Borrowed(_)));
+}
+
+#[test]
+fn removes_dangerous_sequences() {
+    for (input, expected) in [
+        // Window title (OSC 0 / 2) and clipboard write (OSC 52).
+        ("a\x1b]0;title\x07b", "ab"),
+        ("a\x1b]2;title\x1b\\b", "ab"),
+        ("a\x1b]52;c;ZWNobyBwd25lZA==\x07b", "ab"),
+        // Cursor movement, screen clears, full reset.
+        ("a\x1b[2J\x1b[H\x1b[10;10Hb\x1b[Kc", "abc"),
+        ("a\x1bcb\x1b7c\x1b8", "abc"),
+        // Queries that make the terminal answer.
+        ("a\x1b[6nb\x1b[5nc\x1b5n\x1b[c", "abc"),
+        // Control strings and stray controls.
+        ("a\x1bPq#0~\x1b\\b\x1b_Gi=1\x1b\\c", "abc"),
+        ("a\x07b\x08c\x1bd\u{85}e\x00", "abcde"),
+        ("a\u{9b}5nb", "ab"),
+        // Links to other schemes, private SGR variants.
+        (
+            "\x1b]8;;file:///etc/passwd\x07x\x1b]8;;\x07",
+            "x\x1b]8;;\x07",
+        ),
+        ("\x1b]8;;javascript:alert(1)\x07x", "x"),
+        ("a\x1b[>4;2mb", "ab"),
+    ] {
+        assert_eq!(sanitize(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn closes_open_style_and_link() {
+    assert_eq!(sanitize("\x1b[31mred"), "\x1b[31mred\x1b[0m");
+    assert_eq!(
+        sanitize("\x1b]8;;https://x.y\x07open"),
+        "\x1b]8;;https://x.y\x07open\x1b]8;;\x07"
+    );
+    let raw = Sanitizer::new().close_open(false);
+    assert_eq!(raw.sanitize("\x1b[31mred"), "\x1b[31mred");
+}
+
+#[test]
+fn escape_instead_of_remove() {
+    let s = Sanitizer::new().escape_disallowed(true);
+    assert_eq!(
+        s.sanitize("\x1b]0;t\x07\x1b[1mb\x1b[2J\u{9b}5n\x07"),
+        "␛]0;t␇\x1b[1mb␛[2J␛[5n␇\x1b[0m"
+    );
+}
+
+#[test]
+fn policy_knobs() {
+    let no_color = Sanitizer::new().allow_sgr(false);
+    assert_eq!(no_color.sanitize("\x1b[31mred\x1b[0m"), "red");
+    let no_links = Sanitizer::new().allow_hyperlinks(false);
+    assert_eq!(
+        no_links.sanitize("\x1b]8;;https://x.y\x07l\x1b]8;;\x07"),
+        "l"
+    );
+    let mailto = Sanitizer::new().link_schemes(["mailto"]);
+    assert_eq!(
+        mailto.sanitize("\x1b]8;;MAILTO:a@b.c\x07m\x1b]8;;\x07\x1b]8;;https://x.y\x07h"),
+        "\x1b]8;;MAILTO:a@b.c\x07m\x1b]8;;\x07h"
+    );
+    let charset = Sanitizer::new().allow_family(EscapeKind::Charset);
+    assert_eq!(charset.sanitize("\x1b(0q\x1b(B\x1b[A"), "\x1b(0q\x1b(B");
+}
@@ -0,0 +1,143 @@
+//! SGR decoding over the color and style fixtures.
+
+use regex_ansi::{ansi_regex, Color, Sgr, SgrAttr, UnderlineStyle};
+
+fn attrs(seq: &str) -> Vec<SgrAttr> {
+    Sgr::parse(seq)
+        .unwrap_or_else(|| panic!("not SGR: {seq:?}"))
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn resets() {
+    for seq in ["\x1b[m", "\x1b[0m", "\x1bm", "\u{9b}m"] {
+        assert_eq!(attrs(seq), vec![SgrAttr::Reset], "{seq:?}");
+    }
+    assert_eq!(attrs("\x1b[;1m"), vec![SgrAttr::Reset, SgrAttr::Bold]);
+}
+
+#[test]
+fn combined_styles() {
+    assert_eq!(
+        attrs("\x1b[0;33;49;3;9;4m"),
+        vec![
+            SgrAttr::Reset,
+            SgrAttr::Foreground(Color::Basic(3)),
+            SgrAttr::DefaultBackground,
+            SgrAttr::Italic,
+            SgrAttr::Strike,
+            SgrAttr::Underline(UnderlineStyle::Single),
+        ]
+    );
+    assert_eq!(
+        attrs("\x1b[1;2;5;7;8;53m"),
+        vec![
+            SgrAttr::Bold,
+            SgrAttr::Dim,
+            SgrAttr::Blink,
+            SgrAttr::Inverse,
+            SgrAttr::Hidden,
+            SgrAttr::Overline,
+        ]
+    );
+    assert_eq!(
+        attrs("\x1b[22;23;24;25;27;28;29;55;39;49;59m"),
+        vec![
+            SgrAttr::NormalIntensity,
+            SgrAttr::NotItalic,
+            SgrAttr::NoUnderline,
+            SgrAttr::NoBlink,
+            SgrAttr::NoInverse,
+            SgrAttr::NoHidden,
+            SgrAttr::NoStrike,
+            SgrAttr::NoOverline,
+            SgrAttr::DefaultForeground,
+            SgrAttr::DefaultBackground,
+            SgrAttr::DefaultUnderlineColor,
+        ]
+    );
+}
+
+#[test]
+fn underline_variants() {
+    let expected = [
+        SgrAttr::NoUnderline,
+        SgrAttr::Underline(UnderlineStyle::Single),
+        SgrAttr::Underline(UnderlineStyle::Double),
+        SgrAttr::Underline(UnderlineStyle::Curly),
+        SgrAttr::Underline(UnderlineStyle::Dotted),
+        SgrAttr::Underline(UnderlineStyle::Dashed),
+    ];
+    for (n, attr) in expected.into_iter().enumerate() {
+        let seq = format!("\x1b[4:{n}mX");
+        let m = ansi_regex().find(&seq).unwrap().as_str();
+        assert_eq!(attrs(m), vec![attr], "{seq:?}");
+    }
+    assert_eq!(
+        attrs("\x1b[21m"),
+        vec![SgrAttr::Underline(UnderlineStyle::Double)]
+    );
+}
+
+#[test]
+fn sixteen_colors() {
+    for n in 0..8u8 {
+        let fg = format!("\x1b[{}m", 30 + n);
+        let bright_bg = format!("\x1b[{}m", 100 + n);
+        assert_eq!(attrs(&fg), vec![SgrAttr::Foreground(Color::Basic(n))]);
+        assert_eq!(
+            attrs(&bright_bg),
+            vec![SgrAttr::Background(Color::Basic(n + 8))]
+        );
+    }
+}
+
+#[test]
+fn indexed_and_truecolor() {
+    assert_eq!(
+        attrs("\x1b[00;38;5;244m"),
+        vec![SgrAttr::Reset, SgrAttr::Foreground(Color::Indexed(244))]
+    );
+    assert_eq!(
+        attrs("\x1b[48;2;12;34;56;1m"),
+        vec![SgrAttr::Background(Color::Rgb(12, 34, 56)), SgrAttr::Bold]
+    );
+    assert_eq!(
+        attrs("\x1b[38:2::12:34:56m"),
+        vec![SgrAttr::Foreground(Color::Rgb(12, 34, 56))]
+    );
+    assert_eq!(
+        attrs("\x1b[58:2::255:0:0m"),
+        vec![SgrAttr::UnderlineColor(Color::Rgb(255, 0, 0))]
+    );
+    assert_eq!(
+        attrs("\x1b[48:5:200m"),
+        vec![SgrAttr::Background(Color::Indexed(200))]
+    );
+    assert_eq!(
+        attrs("\x1b[38:2:1:2:3m"),
+        vec![SgrAttr::Foreground(Color::Rgb(1, 2, 3))]
+    );
+}
+
+#[test]
+fn malformed_colors_are_unknown() {
+    assert_eq!(attrs("\x1b[38;5;300m"), vec![SgrAttr::Unknown(38)]);
+    assert_eq!(attrs("\x1b[38;2;1m"), vec![SgrAttr::Unknown(38)]);
+    assert_eq!(attrs("\x1b[38:6::255:0:0:128m"), vec![SgrAttr::Unknown(38)]);
+    assert_eq!(attrs("\x1b[38;1m"), vec![SgrAttr::Unknown(38)]);
+}
+
+#[test]
+fn non_sgr_rejected() {
+    for seq in [
+        "\x1b[2J",
+        "\x1b[?25l",
+        "\x1b[>4;2m",
+        "\x1b]0;t\x07",
+        "\x1bA",
+    ] {
+        assert!(Sgr::parse(seq).is_none(), "{seq:?}");
+    }
+}
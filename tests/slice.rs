@@ -0,0 +1,64 @@
+//! Visible column slicing that replays and terminates escape state.
+
+use regex_ansi::{slice_visible, strip_ansi};
+
+#[test]
+fn plain_ranges() {
+    assert_eq!(slice_visible("hello world", 0..5), "hello");
+    assert_eq!(slice_visible("hello world", 6..), "world");
+    assert_eq!(slice_visible("hello world", ..=1), "he");
+    assert_eq!(slice_visible("hello", 3..3), "");
+    assert_eq!(slice_visible("hello", 10..20), "");
+}
+
+#[test]
+fn extreme_bounds() {
+    use std::ops::Bound::{Excluded, Included};
+    assert_eq!(slice_visible("hello", ..=usize::MAX), "hello");
+    assert_eq!(slice_visible("hello", 2..=usize::MAX), "llo");
+    assert_eq!(
+        slice_visible("hello", (Excluded(usize::MAX), Included(usize::MAX))),
+        ""
+    );
+}
+
+#[test]
+fn replays_style_at_start() {
+    let s = "ab\x1b[1;32mcdef\x1b[0mgh";
+    assert_eq!(slice_visible(s, 3..5), "\x1b[1;32mde\x1b[0m");
+    assert_eq!(slice_visible(s, 1..7), "b\x1b[1;32mcdef\x1b[0mg");
+    assert_eq!(slice_visible(s, 6..), "gh");
+}
+
+#[test]
+fn cumulative_state_replayed_as_one_sequence() {
+    let s = "\x1b[31mx\x1b[4my\x1b[48;5;200mz";
+    assert_eq!(slice_visible(s, 2..), "\x1b[4;31;48;5;200mz\x1b[0m");
+}
+
+#[test]
+fn hyperlink_replayed_and_closed() {
+    let open = "\x1b]8;;https://example.com\x07";
+    let s = format!("go {open}to the docs\x1b]8;;\x07!");
+    assert_eq!(slice_visible(&s, 6..9), format!("{open}the\x1b]8;;\x07"));
+    assert_eq!(strip_ansi(&slice_visible(&s, 1..)), "o to the docs!");
+}
+
+#[test]
+fn wide_characters_at_edges() {
+    let s = "a日本b";
+    assert_eq!(slice_visible(s, 1..3), "日");
+    assert_eq!(slice_visible(s, 2..5), "本");
+    assert_eq!(slice_visible(s, 0..2), "a");
+}
+
+#[test]
+fn horizontal_scroll_reassembles() {
+    let s = "\x1b[33mwarn:\x1b[0m disk \x1b[1mnearly\x1b[22m full";
+    let text = strip_ansi(s);
+    let joined: String = (0..text.len())
+        .step_by(4)
+        .map(|i| strip_ansi(&slice_visible(s, i..i + 4)).into_owned())
+        .collect();
+    assert_eq!(joined, text);
+}
@@ -0,0 +1,145 @@
+//! Stream adapters must be independent of how the input is chunked.
+#![cfg(feature = "futures")]
+
+use bytes::Bytes;
+use futures::executor::block_on;
+use futures::stream::{self, StreamExt};
+mod common;
+
+use common::{samples, stripped};
+use regex_ansi::{
+    strip_stream, token_stream, AnsiTokenizer, EscapeKind, StreamToken, Token, TokenStream,
+};
+
+fn chunks(input: &[u8], size: usize) -> impl futures::Stream<Item = Bytes> {
+    let parts: Vec<Bytes> = input.chunks(size).map(Bytes::copy_from_slice).collect();
+    stream::iter(parts)
+}
+
+#[test]
+fn stripped_chunks_match_regex() {
+    for input in samples() {
+        let expected = stripped(&input);
+        for size in 1..=input.len() {
+            let out: Vec<Bytes> = block_on(strip_stream(chunks(&input, size)).collect());
+            assert!(out.iter().all(|c| !c.is_empty()));
+            assert_eq!(out.concat(), expected, "chunks of {size}");
+        }
+    }
+}
+
+// Escapes as (kind, bytes) and the text between them, with text runs merged.
+fn normalize(tokens: Vec<StreamToken>) -> Vec<(Option<EscapeKind>, Vec<u8>)> {
+    let mut out: Vec<(Option<EscapeKind>, Vec<u8>)> = Vec::new();
+    for token in tokens {
+        match token {
+            StreamToken::Text(t) => match out.last_mut() {
+                Some((None, prev)) => prev.extend_from_slice(&t),
+                _ => out.push((None, t.to_vec())),
+            },
+            StreamToken::Escape(kind, seq) => out.push((Some(kind), seq.to_vec())),
+        }
+    }
+    out
+}
+
+#[test]
+fn tokens_match_tokenizer() {
+    for input in samples() {
+        let expected = std::str::from_utf8(&input).ok().map(|s| {
+            normalize(
+                AnsiTokenizer::new(s)
+                    .map(|token| match token {
+                        Token::Text(t) => StreamToken::Text(Bytes::copy_from_slice(t.as_bytes())),
+                        Token::Escape(seq) => StreamToken::Escape(
+                            seq.kind(),
+                            Bytes::copy_from_slice(seq.as_str().as_bytes()),
+                        ),
+                    })
+                    .collect(),
+            )
+        });
+        for size in 1..=input.len() {
+            let tokens = normalize(block_on(token_stream(chunks(&input, size)).collect()));
+            let text: Vec<u8> = tokens
+                .iter()
+                .filter(|(kind, _)| kind.is_none())
+                .flat_map(|(_, t)| t.clone())
+                .collect();
+            assert_eq!(text, stripped(&input), "chunks of {size}");
+            if let Some(expected) = &expected {
+                assert_eq!(&tokens, expected, "chunks of {size}");
+            }
+        }
+    }
+}
+
+#[test]
+fn token_and_strip_streams_agree() {
+    for input in samples() {
+        for size in 1..=input.len() {
+            let tokens = block_on(token_stream(chunks(&input, size)).collect::<Vec<_>>());
+            let text: Vec<u8> = tokens
+                .iter()
+                .filter_map(|t| match t {
+                    StreamToken::Text(t) => Some(t.to_vec()),
+                    StreamToken::Escape(..) => None,
+                })
+                .flatten()
+                .collect();
+            let stripped: Vec<Bytes> = block_on(strip_stream(chunks(&input, size)).collect());
+            assert_eq!(
+                text,
+                stripped.concat(),
+                "{:?} in chunks of {size}",
+                String::from_utf8_lossy(&input)
+            );
+        }
+    }
+}
+
+#[test]
+fn tokens_carry_kind_across_chunks() {
+    let input = chunks(b"\x1b]8;;https://example.com\x07link\x1b]8;;\x07\x1b[1m", 4);
+    let tokens = normalize(block_on(token_stream(input).collect()));
+    let kinds: Vec<_> = tokens.iter().map(|(k, _)| *k).collect();
+    assert_eq!(
+        kinds,
+        [
+            Some(EscapeKind::Osc),
+            None,
+            Some(EscapeKind::Osc),
+            Some(EscapeKind::Csi)
+        ]
+    );
+    assert_eq!(tokens[1].1, b"link");
+}
+
+#[test]
+fn unfinished_sequences_are_bounded() {
+    let mut input = b"\x1b]0;".to_vec();
+    input.extend([b'a'; 1000]);
+    input.extend_from_slice(b"\x1b[1m");
+    let tokens = block_on(TokenStream::with_max_pending(chunks(&input, 7), 32).collect());
+    assert_eq!(
+        normalize(tokens),
+        [
+            (None, input[..input.len() - 4].to_vec()),
+            (Some(EscapeKind::Csi), b"\x1b[1m".to_vec())
+        ]
+    );
+
+    // Thousands of CSI parameter groups must not exhaust the stack.
+    let mut input = b"\x1b[".to_vec();
+    for _ in 0..50_000 {
+        input.extend_from_slice(b"1;");
+    }
+    let tokens: Vec<StreamToken> = block_on(token_stream(chunks(&input, 4096)).collect());
+    let bytes: Vec<u8> = tokens
+        .iter()
+        .flat_map(|t| match t {
+            StreamToken::Text(b) | StreamToken::Escape(_, b) => b.to_vec(),
+        })
+        .collect();
+    assert_eq!(bytes, input);
+}
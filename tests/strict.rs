@@ -0,0 +1,83 @@
+//! Fixtures contrasting the upstream-parity CSI branch with the ECMA-48 one.
+
+use regex_ansi::{ansi_regex, ansi_regex_strict};
+
+// (input, upstream match, strict match)
+const FIXTURES: &[(&str, Option<&str>, &str)] = &[
+    // DECSCUSR: intermediate byte before the final.
+    ("\x1b[2 q", Some("\x1b[2"), "\x1b[2 q"),
+    // ICH: `@` is a valid final byte.
+    ("\x1b[@", None, "\x1b[@"),
+    ("\x1b[5@", Some("\x1b[5"), "\x1b[5@"),
+    // ECH and VPA finals outside the upstream class.
+    ("\x1b[5X", Some("\x1b[5"), "\x1b[5X"),
+    ("\x1b[10d", Some("\x1b[10"), "\x1b[10d"),
+    ("\x1b[3`", Some("\x1b[3"), "\x1b[3`"),
+    // Parameters longer than four digits.
+    ("\x1b[12345;1H", Some("\x1b[12345"), "\x1b[12345;1H"),
+    // Private marker other than `?`.
+    ("\x1b[>4;2m", Some("\x1b[>"), "\x1b[>4;2m"),
+    ("\x1b[<0;10;20M", Some("\x1b[<"), "\x1b[<0;10;20M"),
+    ("\x1b[=1c", Some("\x1b[="), "\x1b[=1c"),
+    // Intermediates: DECSTR and DECSCL.
+    ("\x1b[!p", None, "\x1b[!p"),
+    ("\x1b[62;1\"p", Some("\x1b[62;1"), "\x1b[62;1\"p"),
+    ("\u{9b}2 q", Some("\u{9b}2"), "\u{9b}2 q"),
+];
+
+// Sequences both patterns match identically.
+const COMMON: &[&str] = &[
+    "\x1b[31m",
+    "\x1b[0m",
+    "\x1b[m",
+    "\x1b[?25l",
+    "\x1b[?1049h",
+    "\x1b[38;5;160m",
+    "\x1b[38;2;10;20;30m",
+    "\x1b[4:3m",
+    "\x1b[176;176H",
+    "\x1b[2J",
+    "\x1b[K",
+    "\u{9b}31m",
+    "\x1b]8;;https://example.com\x07",
+    "\x1b(B",
+    "\x1b#8",
+    "\x1b7",
+    "\x1bc",
+    "\x1b5n",
+];
+
+#[test]
+fn upstream_behaviour() {
+    for &(input, expected, _) in FIXTURES {
+        let m = ansi_regex().find(input).map(|m| m.as_str());
+        assert_eq!(m, expected, "{input:?}");
+    }
+}
+
+#[test]
+fn strict_behaviour() {
+    for &(input, _, expected) in FIXTURES {
+        let m = ansi_regex_strict().find(input).map(|m| m.as_str());
+        assert_eq!(m, Some(expected), "{input:?}");
+        let framed = format!("a{input}b");
+        assert_eq!(ansi_regex_strict().replace_all(&framed, ""), "ab");
+    }
+}
+
+#[test]
+fn common_sequences_agree() {
+    for &seq in COMMON {
+        let up = ansi_regex().find(seq).map(|m| m.as_str());
+        let strict = ansi_regex_strict().find(seq).map(|m| m.as_str());
+        assert_eq!(up, Some(seq), "{seq:?}");
+        assert_eq!(strict, Some(seq), "{seq:?}");
+    }
+}
+
+#[test]
+fn strict_leaves_incomplete_sequences() {
+    for s in ["\x1b[", "\x1b[12;", "\x1b[1 ", "\x1b[?25"] {
+        assert!(ansi_regex_strict().find(s).is_none(), "{s:?}");
+    }
+}
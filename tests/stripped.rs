@@ -0,0 +1,72 @@
+//! Offset mapping between stripped and original text.
+
+use regex_ansi::{ansi_regex, strip_ansi, strip_ansi_mapped};
+
+#[test]
+fn text_matches_strip_ansi() {
+    let s = "Start \x1b[31mRED\x1b[0m and \x1b]0;Title\x07tail";
+    let st = strip_ansi_mapped(s);
+    assert_eq!(st.as_str(), strip_ansi(s));
+    assert_eq!(st.to_string(), "Start RED and tail");
+}
+
+#[test]
+fn search_hit_maps_back_to_original() {
+    let s = "foo \x1b[1mbar\x1b[22m \x1b[4mba\x1b[24mz";
+    let st = strip_ansi_mapped(s);
+    let hit = st.as_str().find("bar").unwrap();
+    let range = st.to_original_range(hit..hit + 3);
+    assert_eq!(&s[range], "bar");
+    // A hit spanning a sequence keeps it inside the original range.
+    let hit = st.as_str().find("baz").unwrap();
+    let range = st.to_original_range(hit..hit + 3);
+    assert_eq!(&s[range.clone()], "ba\x1b[24mz");
+    assert_eq!(st.to_stripped_range(range), hit..hit + 3);
+}
+
+#[test]
+fn every_visible_byte_round_trips() {
+    let s = "\x1b[38;5;160mé日\x1b[0m\x1b]8;;https://x\x1b\\a\x1b]8;;\x1b\\";
+    let st = strip_ansi_mapped(s);
+    for (i, _) in st.as_str().char_indices() {
+        let o = st.to_original(i);
+        assert_eq!(st.to_stripped(o), i);
+        assert_eq!(s[o..].chars().next(), st.as_str()[i..].chars().next());
+    }
+}
+
+#[test]
+fn offsets_inside_escapes_collapse() {
+    let s = "ab\x1b[31mcd";
+    let st = strip_ansi_mapped(s);
+    let esc = ansi_regex().find(s).unwrap().range();
+    for o in esc {
+        assert_eq!(st.to_stripped(o), 2);
+    }
+    assert_eq!(st.to_original(2), 7);
+    assert_eq!(st.to_original(4), 9);
+    assert_eq!(st.to_original_range(0..2), 0..2);
+}
+
+#[test]
+fn columns_and_offsets() {
+    let st = strip_ansi_mapped("\x1b[1ma日本e\u{301}b\x1b[0m");
+    assert_eq!(st.as_str(), "a日本e\u{301}b");
+    assert_eq!(st.width(), 7);
+    let cols: Vec<_> = st
+        .as_str()
+        .char_indices()
+        .map(|(i, _)| st.column_of(i))
+        .collect();
+    assert_eq!(cols, vec![0, 1, 3, 5, 5, 6]);
+    let offsets: Vec<_> = (0..=8).map(|c| st.offset_of_column(c)).collect();
+    assert_eq!(offsets, vec![0, 1, 1, 4, 4, 7, 10, 11, 11]);
+}
+
+#[test]
+fn ascii_needs_no_index_entries() {
+    let st = strip_ansi_mapped("plain ascii text");
+    assert_eq!(st.to_original(5), 5);
+    assert_eq!(st.column_of(7), 7);
+    assert_eq!(st.offset_of_column(3), 3);
+}
@@ -0,0 +1,120 @@
+//! Cumulative style tracking across SGR sequences.
+
+use regex_ansi::{
+    styled_segments, tokenize, Color, EscapeSequence, Sgr, Style, StyleState, UnderlineStyle,
+};
+
+fn state_after(s: &str) -> Style {
+    let mut state = StyleState::new();
+    for token in tokenize(s) {
+        state.feed(&token);
+    }
+    state.style().clone()
+}
+
+#[test]
+fn combined_sequence_after_reset() {
+    let style = state_after("\x1b[1;31m\x1b[0;33;49;3;9;4m");
+    let mut expected = Style::default();
+    expected.italic = true;
+    expected.strike = true;
+    expected.underline = Some(UnderlineStyle::Single);
+    expected.foreground = Some(Color::Basic(3));
+    assert_eq!(style, expected);
+}
+
+#[test]
+fn full_and_short_resets() {
+    for reset in ["\x1b[0m", "\x1b[m", "\x1bm", "\x1bc"] {
+        let s = format!("\x1b[1;4;38;5;200;48;2;1;2;3m{reset}");
+        assert!(state_after(&s).is_default(), "{reset:?}");
+    }
+}
+
+#[test]
+fn partial_resets() {
+    let all = "\x1b[1;2;3;4;9;31;42;58;5;9m";
+    type Check = fn(&Style) -> bool;
+    let cases: [(&str, Check); 6] = [
+        ("\x1b[22m", |s| !s.bold && !s.dim && s.italic),
+        ("\x1b[23m", |s| !s.italic && s.bold),
+        ("\x1b[24m", |s| s.underline.is_none() && s.strike),
+        ("\x1b[39m", |s| {
+            s.foreground.is_none() && s.background.is_some()
+        }),
+        ("\x1b[49m", |s| {
+            s.background.is_none() && s.foreground.is_some()
+        }),
+        ("\x1b[59m", |s| {
+            s.underline_color.is_none() && s.underline.is_some()
+        }),
+    ];
+    for (seq, check) in cases {
+        let style = state_after(&format!("{all}{seq}"));
+        assert!(check(&style), "{seq:?} -> {style:?}");
+    }
+}
+
+#[test]
+fn non_sgr_sequences_ignored() {
+    let style = state_after("\x1b[1m\x1b[2J\x1b]0;title\x07\x1b(B\x1b[?25l");
+    assert!(style.bold);
+    let mut expected = Style::default();
+    expected.bold = true;
+    assert_eq!(style, expected);
+}
+
+#[test]
+fn segments_carry_style() {
+    let segs: Vec<_> = styled_segments("a\x1b[31mb\x1b[1mc\x1b[39md\x1b[0me").collect();
+    let red = Some(Color::Basic(1));
+    assert_eq!(segs.len(), 5);
+    assert!(segs[0].0.is_default());
+    assert_eq!((segs[1].0.foreground, segs[1].1), (red, "b"));
+    assert!(segs[2].0.bold && segs[2].0.foreground == red);
+    assert!(segs[3].0.bold && segs[3].0.foreground.is_none());
+    assert!(segs[4].0.is_default());
+}
+
+#[test]
+fn to_sgr_round_trips() {
+    let samples = [
+        "\x1b[1;3;4:3;7;9;53m",
+        "\x1b[2;5;8;91;104m",
+        "\x1b[38;5;244;48;2;12;34;56;58;2;255;0;0m",
+        "\x1b[4:5;58:5:3m",
+    ];
+    for s in samples {
+        let style = state_after(s);
+        let sgr = style.to_sgr();
+        assert!(Sgr::parse(&sgr).is_some(), "{sgr:?}");
+        assert_eq!(state_after(&sgr), style, "{s:?} -> {sgr:?}");
+    }
+    assert_eq!(Style::default().to_sgr(), "");
+}
+
+#[test]
+fn hyperlink_tracking() {
+    let mut state = StyleState::new();
+    let open = "\x1b]8;id=7;https://example.com\x07";
+    for token in tokenize(&format!("\x1b[1m{open}label")) {
+        state.feed(&token);
+    }
+    assert_eq!(state.hyperlink(), Some(open));
+    assert_eq!(state.open_sequence(), format!("\x1b[1m{open}"));
+    assert_eq!(state.close_sequence(), "\x1b]8;;\x07\x1b[0m");
+    for token in tokenize("\x1b]8;;\x07\x1b[22m") {
+        state.feed(&token);
+    }
+    assert_eq!(state.hyperlink(), None);
+    assert_eq!(state.close_sequence(), "");
+}
+
+#[test]
+fn malformed_hyperlink_ignored() {
+    let mut state = StyleState::new();
+    for seq in ["\x1b]8;", "\x1b]8;\x07", "\x1b]8;x", "\x1b]8;;"] {
+        state.apply_escape(&EscapeSequence::new(seq));
+        assert_eq!(state.hyperlink(), None, "{seq:?}");
+    }
+}
@@ -0,0 +1,72 @@
+//! Token stream classification over the upstream pattern branches.
+
+use regex_ansi::{tokenize, AnsiTokenizer, EscapeKind, Token};
+
+fn escapes(s: &str) -> Vec<(EscapeKind, &str)> {
+    tokenize(s)
+        .filter_map(|t| match t {
+            Token::Escape(e) => Some((e.kind(), e.as_str())),
+            Token::Text(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn text_and_escapes_interleave() {
+    let s = "a\x1b[31mred\x1b[0m\x1b[1mb";
+    let tokens: Vec<_> = tokenize(s).map(|t| t.as_str()).collect();
+    assert_eq!(
+        tokens,
+        vec!["a", "\x1b[31m", "red", "\x1b[0m", "\x1b[1m", "b"]
+    );
+    assert!(matches!(tokenize(s).next(), Some(Token::Text("a"))));
+}
+
+#[test]
+fn tokens_reassemble_input() {
+    let s = "\x1b]0;title\x07start \x1b(B\x1b#8\x1bM mid \u{9b}2Jend\x1b";
+    let joined: String = tokenize(s).map(|t| t.as_str()).collect();
+    assert_eq!(joined, s);
+}
+
+#[test]
+fn classifies_each_branch() {
+    let cases = [
+        ("\x1b]8;;https://example.com\x07", EscapeKind::Osc),
+        ("\x1b]0;title\x1b\\", EscapeKind::Osc),
+        ("\x1b[38;5;123m", EscapeKind::Csi),
+        ("\x1b[?25l", EscapeKind::Csi),
+        ("\u{9b}31m", EscapeKind::Csi),
+        ("\x1b(B", EscapeKind::Charset),
+        ("\x1b)0", EscapeKind::Charset),
+        ("\x1b#8", EscapeKind::DecHash),
+        ("\x1bA", EscapeKind::Short),
+        ("\x1b7", EscapeKind::Short),
+        ("\x1b8", EscapeKind::Short),
+        ("\x1bc", EscapeKind::Short),
+        ("\x1bm", EscapeKind::Short),
+        ("\x1b5n", EscapeKind::DeviceStatus),
+        ("\x1b0n", EscapeKind::DeviceStatus),
+    ];
+    for (seq, kind) in cases {
+        let wrapped = format!("x{seq}y");
+        assert_eq!(escapes(&wrapped), vec![(kind, seq)], "{seq:?}");
+    }
+}
+
+#[test]
+fn plain_text_is_single_token() {
+    let tokens: Vec<_> = tokenize("[not an escape]").collect();
+    assert_eq!(tokens, vec![Token::Text("[not an escape]")]);
+    assert_eq!(tokenize("").next(), None);
+}
+
+#[test]
+fn offset_tracks_position() {
+    let mut t = AnsiTokenizer::new("ab\x1b[1mcd");
+    assert_eq!(t.offset(), 0);
+    t.next();
+    assert_eq!(t.offset(), 2);
+    t.next();
+    assert_eq!(t.offset(), 6);
+}
@@ -0,0 +1,182 @@
+//! Async adapters must strip exactly like the regex across poll boundaries.
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use common::{samples, stripped};
+use regex_ansi::{AsyncStripReader, AsyncStripWriter};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+// Reader that hands out at most `step` bytes per poll, pending every other poll.
+struct Trickle<'a> {
+    data: &'a [u8],
+    step: usize,
+    ready: bool,
+}
+
+impl AsyncRead for Trickle<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.ready = !self.ready;
+        if !self.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let n = self.step.min(buf.remaining()).min(self.data.len());
+        buf.put_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn reader_matches_regex_for_every_chunk_size() {
+    for input in samples() {
+        let expected = stripped(&input);
+        for step in 1..=input.len() {
+            let mut out = Vec::new();
+            AsyncStripReader::new(Trickle {
+                data: &input,
+                step,
+                ready: false,
+            })
+            .read_to_end(&mut out)
+            .await
+            .unwrap();
+            assert_eq!(
+                out,
+                expected,
+                "{:?} in reads of {step}",
+                String::from_utf8_lossy(&input)
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn reader_lines() {
+    let data = b"\x1b[32mok\x1b[0m first\n\x1b]0;t\x07second\n";
+    let reader = AsyncStripReader::new(Trickle {
+        data,
+        step: 3,
+        ready: false,
+    });
+    let mut lines = reader.lines();
+    assert_eq!(
+        lines.next_line().await.unwrap().as_deref(),
+        Some("ok first")
+    );
+    assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("second"));
+    assert_eq!(lines.next_line().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn writer_matches_regex_for_every_chunk_size() {
+    for input in samples() {
+        let expected = stripped(&input);
+        for chunk in 1..=input.len() {
+            let mut w = AsyncStripWriter::new(Vec::new());
+            for part in input.chunks(chunk) {
+                w.write_all(part).await.unwrap();
+            }
+            w.shutdown().await.unwrap();
+            assert_eq!(
+                w.into_inner(),
+                expected,
+                "{:?} in chunks of {chunk}",
+                String::from_utf8_lossy(&input)
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn writer_respects_backpressure() {
+    // A tiny pipe only accepts more once the other end has been read.
+    let (tx, mut rx) = tokio::io::duplex(4);
+    let mut input = Vec::new();
+    for i in 0..200 {
+        input.extend_from_slice(format!("\x1b[3{}mline {i}\x1b[0m\n", i % 8).as_bytes());
+    }
+    let expected = stripped(&input);
+    let write = async {
+        let mut w = AsyncStripWriter::new(tx);
+        for part in input.chunks(5) {
+            w.write_all(part).await.unwrap();
+        }
+        w.shutdown().await.unwrap();
+    };
+    let read = async {
+        let mut out = Vec::new();
+        rx.read_to_end(&mut out).await.unwrap();
+        out
+    };
+    let ((), out) = tokio::join!(write, read);
+    assert_eq!(out, expected);
+}
+
+#[tokio::test]
+async fn writer_holds_tail_until_shutdown() {
+    let mut w = AsyncStripWriter::new(Vec::new());
+    w.write_all(b"abc\x1b[38;5").await.unwrap();
+    w.flush().await.unwrap();
+    assert_eq!(w.get_ref(), b"abc");
+    w.write_all(b";1mdef\x1b]0;t").await.unwrap();
+    assert_eq!(w.get_ref(), b"abcdef");
+    w.shutdown().await.unwrap();
+    assert_eq!(w.get_ref(), b"abcdef\x1b]0;t");
+}
+
+// Writer whose every other call fails, accepting at most two bytes otherwise.
+#[derive(Default)]
+struct Flaky {
+    data: Vec<u8>,
+    fail: bool,
+}
+
+impl AsyncWrite for Flaky {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.fail = !self.fail;
+        if self.fail {
+            return Poll::Ready(Err(io::Error::other("try again")));
+        }
+        let n = buf.len().min(2);
+        self.data.extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn writer_retries_after_inner_errors() {
+    let input = "a\x1b[1mbc\x1b]0;t\x07def\x1b[0mghi ě\x1b[38;5;1mjkl\n".as_bytes();
+    for chunk in 1..=input.len() {
+        let mut w = AsyncStripWriter::new(Flaky::default());
+        for mut part in input.chunks(chunk) {
+            while !part.is_empty() {
+                if let Ok(n) = w.write(part).await {
+                    part = &part[n..];
+                }
+            }
+        }
+        while w.shutdown().await.is_err() {}
+        assert_eq!(w.into_inner().data, stripped(input), "chunks of {chunk}");
+    }
+}
@@ -0,0 +1,72 @@
+//! Column truncation that keeps styles balanced.
+
+use regex_ansi::{strip_ansi, truncate_visible, visible_width};
+use std::borrow::Cow;
+
+#[test]
+fn fitting_input_is_borrowed() {
+    let s = "\x1b[31mred\x1b[0m";
+    assert!(matches!(truncate_visible(s, 3, "…"), Cow::Borrowed(_)));
+    assert!(matches!(
+        truncate_visible("plain", 10, "…"),
+        Cow::Borrowed(_)
+    ));
+}
+
+#[test]
+fn plain_text() {
+    assert_eq!(truncate_visible("hello world", 8, "..."), "hello...");
+    assert_eq!(truncate_visible("hello world", 5, ""), "hello");
+}
+
+#[test]
+fn ellipsis_keeps_style_and_resets() {
+    let s = "\x1b[1;31mstatus: failing badly\x1b[0m";
+    let out = truncate_visible(s, 10, "…");
+    assert_eq!(out, "\x1b[1;31mstatus: f…\x1b[0m");
+    assert_eq!(visible_width(&out), 10);
+}
+
+#[test]
+fn style_closed_before_cut_stays_closed() {
+    let out = truncate_visible("\x1b[32mok\x1b[0m and more text", 5, "…");
+    assert_eq!(out, "\x1b[32mok\x1b[0m a…");
+}
+
+#[test]
+fn never_splits_sequences() {
+    let s = "ab\x1b[38;5;160mcd\x1b[48:2::1:2:3mef";
+    for cols in 0..6 {
+        let out = truncate_visible(s, cols, "");
+        assert!(visible_width(&out) <= cols);
+        assert!(s.starts_with(out.trim_end_matches("\x1b[0m")), "{out:?}");
+    }
+}
+
+#[test]
+fn wide_characters_not_split() {
+    let out = truncate_visible("日本語テキスト", 5, "…");
+    assert_eq!(out, "日本…");
+    assert_eq!(truncate_visible("日本語", 3, ""), "日");
+}
+
+#[test]
+fn hyperlink_closed_with_same_terminator() {
+    let s = "\x1b]8;;https://example.com\x1b\\a long link label\x1b]8;;\x1b\\";
+    let out = truncate_visible(s, 7, "...");
+    assert_eq!(
+        out,
+        "\x1b]8;;https://example.com\x1b\\a lo...\x1b]8;;\x1b\\"
+    );
+    let bel = "\x1b[4m\x1b]8;id=1;https://example.com\x07click here\x1b]8;;\x07\x1b[24m";
+    assert_eq!(
+        truncate_visible(bel, 4, "…"),
+        "\x1b[4m\x1b]8;id=1;https://example.com\x07cli…\x1b]8;;\x07\x1b[0m"
+    );
+}
+
+#[test]
+fn ellipsis_wider_than_limit() {
+    assert_eq!(truncate_visible("abcdef", 2, "..."), "..");
+    assert_eq!(strip_ansi(&truncate_visible("\x1b[1mabc", 0, "…")), "");
+}
@@ -0,0 +1,35 @@
+//! Readable rendering of sequences and stray controls.
+
+use regex_ansi::visualize;
+
+#[test]
+fn renders_each_family() {
+    for (input, expected) in [
+        ("\x1b[31mred\x1b[m", "<CSI 31 m>red<CSI m>"),
+        ("\x1b[?25l", "<CSI ?25 l>"),
+        ("\u{9b}2J", "<C1 CSI 2 J>"),
+        (
+            "\x1b]8;;https://x.y\x07a\x1b]8;;\x1b\\",
+            "<OSC 8;;https://x.y BEL>a<OSC 8;; ST>",
+        ),
+        ("\x1b]0;t\u{9c}", "<OSC 0;t C1 ST>"),
+        ("\x1b7\x1b8", "<ESC 7><ESC 8>"),
+        ("\x1b(B\x1b#8", "<ESC ( B><ESC # 8>"),
+        ("\x1b5n", "<ESC 5 n>"),
+    ] {
+        assert_eq!(visualize(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn names_stray_controls() {
+    assert_eq!(visualize("a\x1bb\x07\x00\x7f"), "a<ESC>b<BEL><NUL><DEL>");
+    assert_eq!(visualize("\u{85}\u{9c}\x1b["), "<NEL><ST><ESC>[");
+    assert_eq!(visualize("\x1b]0;t"), "<ESC>]0;t");
+}
+
+#[test]
+fn keeps_plain_text_and_layout() {
+    assert_eq!(visualize("日本\tx\n"), "日本\tx\n");
+    assert_eq!(visualize("a\rb"), "a<CR>b");
+}
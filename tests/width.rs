@@ -0,0 +1,46 @@
+//! Visible column width of strings with escape sequences.
+
+use regex_ansi::{text_width, visible_width};
+
+#[test]
+fn ignores_escapes() {
+    assert_eq!(visible_width("\x1b[31mred\x1b[0m"), 3);
+    assert_eq!(
+        visible_width("\x1b]8;;https://example.com\x07link\x1b]8;;\x07"),
+        4
+    );
+    assert_eq!(visible_width("\x1b[38:2::12:34:56m\x1b(B\x1b#8ok"), 2);
+    assert_eq!(visible_width(""), 0);
+}
+
+#[test]
+fn east_asian_wide() {
+    assert_eq!(visible_width("\x1b[1m日本語\x1b[0m"), 6);
+    assert_eq!(visible_width("ｱｲ"), 2);
+    assert_eq!(text_width("ａｂ"), 4);
+}
+
+#[test]
+fn combining_marks_and_joiners() {
+    assert_eq!(visible_width("e\u{301}"), 1);
+    assert_eq!(visible_width("a\u{200d}b"), 2);
+    assert_eq!(visible_width("\u{1100}\u{1161}"), 2);
+}
+
+#[test]
+fn emoji_sequences() {
+    assert_eq!(
+        visible_width("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"),
+        2
+    );
+    assert_eq!(visible_width("\u{1F1FA}\u{1F1F8}"), 2);
+    assert_eq!(visible_width("\u{2764}\u{FE0F}"), 2);
+    assert_eq!(visible_width("\u{1F44D}\u{1F3FD}!"), 3);
+}
+
+#[test]
+fn controls_are_zero_width() {
+    assert_eq!(visible_width("a\tb\r\n"), 2);
+    // A lone ESC is not a sequence but does not advance the cursor either.
+    assert_eq!(visible_width("\x1bx"), 1);
+}
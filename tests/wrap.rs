@@ -0,0 +1,81 @@
+//! Word wrapping with styles re-opened on every line.
+
+use regex_ansi::{strip_ansi, visible_width, wrap, WrapOptions};
+
+fn plain(lines: &[String]) -> Vec<String> {
+    lines.iter().map(|l| strip_ansi(l).into_owned()).collect()
+}
+
+#[test]
+fn wraps_plain_words() {
+    let lines = wrap("the quick brown fox jumps", 10, &WrapOptions::new());
+    assert_eq!(lines, vec!["the quick", "brown fox", "jumps"]);
+}
+
+#[test]
+fn reopens_and_closes_style_per_line() {
+    let s = "\x1b[31mred words that wrap\x1b[0m done";
+    let lines = wrap(s, 10, &WrapOptions::new());
+    assert_eq!(
+        lines,
+        vec![
+            "\x1b[31mred words\x1b[0m",
+            "\x1b[31mthat wrap\x1b[0m",
+            "done",
+        ]
+    );
+}
+
+#[test]
+fn reopens_hyperlink_per_line() {
+    let open = "\x1b]8;;https://example.com\x1b\\";
+    let s = format!("see {open}the manual page\x1b]8;;\x1b\\ now");
+    let lines = wrap(&s, 8, &WrapOptions::new());
+    assert_eq!(plain(&lines), vec!["see the", "manual", "page now"]);
+    assert_eq!(lines[0], format!("see {open}the\x1b]8;;\x1b\\"));
+    assert_eq!(lines[1], format!("{open}manual\x1b]8;;\x1b\\"));
+    assert_eq!(lines[2], format!("{open}page\x1b]8;;\x1b\\ now"));
+}
+
+#[test]
+fn hard_breaks_long_words() {
+    let s = "ab \x1b[1mabcdefghij\x1b[22m";
+    let soft = wrap(s, 4, &WrapOptions::new());
+    assert_eq!(plain(&soft), vec!["ab", "abcdefghij"]);
+    let hard = wrap(s, 4, &WrapOptions::new().hard(true));
+    assert_eq!(plain(&hard), vec!["ab a", "bcde", "fghi", "j"]);
+    assert_eq!(hard[1], "\x1b[1mbcde\x1b[0m");
+    assert!(hard.iter().all(|l| visible_width(l) <= 4));
+}
+
+#[test]
+fn wide_characters_respect_width() {
+    let lines = wrap("日本語のテキスト", 5, &WrapOptions::new().hard(true));
+    assert_eq!(plain(&lines), vec!["日本", "語の", "テキ", "スト"]);
+}
+
+#[test]
+fn trim_controls_edge_whitespace() {
+    let s = "  lead  and   trail  ";
+    assert_eq!(wrap(s, 9, &WrapOptions::new()), vec!["lead  and", "trail"]);
+    let kept = wrap(s, 9, &WrapOptions::new().trim(false));
+    assert_eq!(kept, vec!["  lead  ", "and   ", "trail  "]);
+}
+
+#[test]
+fn indentation() {
+    let opts = WrapOptions::new()
+        .initial_indent("* ")
+        .subsequent_indent("  ");
+    let lines = wrap("\x1b[2malpha beta gamma delta", 12, &opts);
+    assert_eq!(plain(&lines), vec!["* alpha beta", "  gamma", "  delta"]);
+    assert_eq!(lines[1], "  \x1b[2mgamma\x1b[0m");
+}
+
+#[test]
+fn keeps_newlines_and_blank_lines() {
+    let lines = wrap("\x1b[4mone\n\ntwo three\x1b[24m", 5, &WrapOptions::new());
+    assert_eq!(plain(&lines), vec!["one", "", "two", "three"]);
+    assert_eq!(lines[1], "");
+    assert_eq!(lines[2], "\x1b[4mtwo\x1b[0m");
+}